@@ -12,177 +12,1393 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use ffi::event::*;
+//! Safe wrappers for the events reported by SDL.
 
+use std::any::{Any, AnyRefExt};
+use std::cast::transmute;
+use std::cell::Cell;
+use std::ptr;
+use std::rc::Rc;
+use std::str::raw::from_c_str;
+use libc::{c_char, c_int, c_void, size_t};
+
+use ffi::events::*;
+use ffi::gesture::SDL_GestureID;
+use ffi::joystick::{SDL_Hat, SDL_JoystickID};
+use ffi::keycode::SDL_Keymod;
+use ffi::scancode::SDL_Scancode;
+use ffi::stdinc::{SDL_free, SDL_malloc};
+use ffi::thread::{SDL_ThreadID, SDL_threadID};
+use ffi::video::*;
+use ffi::touch::{SDL_FingerID, SDL_TouchID};
+
+use {Sdl, SdlResult, get_error};
+
+/// An event paired with the time, in milliseconds since SDL was initialised,
+/// that it was triggered.
 pub type EventStamped = (u32, Event);
 
-pub struct PressedState {
+/// The type of an event, as used to query and filter the queue. The
+/// predicates on this type, such as `is_key` and `is_motion`, can be used to
+/// test which kind of event it describes.
+pub type EventType = SDL_EventType;
+
+/// A range of related event types.
+#[deriving(Clone, Eq)]
+pub enum EventCategory {
+    /// `Quit` and the application lifecycle events.
+    AppEvents,
+    /// `WindowEvent` and `SysWm`.
+    WindowEvents,
+    /// Key presses, text editing and text input.
+    KeyboardEvents,
+    MouseEvents,
+    JoystickEvents,
+    ControllerEvents,
+    TouchEvents,
+    GestureEvents,
+    ClipboardEvents,
+    DropEvents,
+    /// Raw user events and custom events.
+    UserEvents,
+}
+
+impl EventCategory {
+    /// Returns the first and last event types in the category, inclusive.
+    pub fn range(&self) -> (EventType, EventType) {
+        match *self {
+            AppEvents           => (SDL_QUIT,                   SDL_APP_DIDENTERFOREGROUND),
+            WindowEvents        => (SDL_WINDOWEVENT,            SDL_SYSWMEVENT),
+            KeyboardEvents      => (SDL_KEYDOWN,                SDL_TEXTINPUT),
+            MouseEvents         => (SDL_MOUSEMOTION,            SDL_MOUSEWHEEL),
+            JoystickEvents      => (SDL_JOYAXISMOTION,          SDL_JOYDEVICEREMOVED),
+            ControllerEvents    => (SDL_CONTROLLERAXISMOTION,   SDL_CONTROLLERDEVICEREMAPPED),
+            TouchEvents         => (SDL_FINGERDOWN,             SDL_FINGERMOTION),
+            GestureEvents       => (SDL_DOLLARGESTURE,          SDL_MULTIGESTURE),
+            ClipboardEvents     => (SDL_CLIPBOARDUPDATE,        SDL_CLIPBOARDUPDATE),
+            DropEvents          => (SDL_DROPFILE,               SDL_DROPFILE),
+            UserEvents          => (SDL_USEREVENT,              SDL_EventType::new_user_event(SDL_LASTEVENT.bits() - 1)),
+        }
+    }
+
+    /// Returns `true` if the event type is part of this category.
+    pub fn contains(&self, event_type: EventType) -> bool {
+        let (min, max) = self.range();
+        min.bits() <= event_type.bits() && event_type.bits() <= max.bits()
+    }
+}
+
+/// The state of a key or button.
+#[deriving(Clone, Eq)]
+pub enum PressedState {
     Pressed,
     Released,
 }
 
+impl PressedState {
+    fn from_sdl(state: SDL_ButtonState) -> PressedState {
+        match state {
+            SDL_PRESSED  => Pressed,
+            SDL_RELEASED => Released,
+        }
+    }
+
+    fn to_sdl(&self) -> SDL_ButtonState {
+        match *self {
+            Pressed  => SDL_PRESSED,
+            Released => SDL_RELEASED,
+        }
+    }
+}
+
+/// The change reported by a joystick device event.
+#[deriving(Clone, Eq)]
+pub enum JoyDeviceChange {
+    JoyAdded,
+    JoyRemoved,
+}
+
+/// The change reported by a game controller device event.
+#[deriving(Clone, Eq)]
+pub enum DeviceChange {
+    DeviceAdded,
+    DeviceRemoved,
+    DeviceRemapped,
+}
+
+/// The change reported by a touch finger event.
+#[deriving(Clone, Eq)]
+pub enum FingerChange {
+    FingerDown,
+    FingerUp,
+    FingerMotion,
+}
+
+/// The change described by a `WindowEvent`.
+#[deriving(Clone, Eq)]
+pub enum WindowEventKind {
+    Shown,
+    Hidden,
+    /// The window was exposed and should be redrawn.
+    Exposed,
+    /// The window was moved so that its top left corner is at `x`, `y`.
+    Moved { x: i32, y: i32 },
+    /// The window was resized by the user or window manager.
+    Resized { w: i32, h: i32 },
+    /// The size of the window changed, either by being resized or through an
+    /// API call. This is always reported before `Resized`.
+    SizeChanged { w: i32, h: i32 },
+    Minimized,
+    Maximized,
+    /// The window was restored to its normal size and position.
+    Restored,
+    /// The window gained mouse focus.
+    Enter,
+    /// The window lost mouse focus.
+    Leave,
+    /// The window gained keyboard focus.
+    FocusGained,
+    /// The window lost keyboard focus.
+    FocusLost,
+    /// The window manager requested that the window be closed.
+    Close,
+    /// An event id that this version of the bindings does not recognise.
+    UnknownWindowEvent { event_id: u8, data1: i32, data2: i32 },
+}
+
+impl WindowEventKind {
+    /// Decodes the event id and data fields of an `SDL_WindowEvent`.
+    pub fn from_sdl(event_id: u8, data1: i32, data2: i32) -> WindowEventKind {
+        match event_id {
+            e if e == SDL_WINDOWEVENT_SHOWN as u8           => Shown,
+            e if e == SDL_WINDOWEVENT_HIDDEN as u8          => Hidden,
+            e if e == SDL_WINDOWEVENT_EXPOSED as u8         => Exposed,
+            e if e == SDL_WINDOWEVENT_MOVED as u8           => Moved { x: data1, y: data2 },
+            e if e == SDL_WINDOWEVENT_RESIZED as u8         => Resized { w: data1, h: data2 },
+            e if e == SDL_WINDOWEVENT_SIZE_CHANGED as u8    => SizeChanged { w: data1, h: data2 },
+            e if e == SDL_WINDOWEVENT_MINIMIZED as u8       => Minimized,
+            e if e == SDL_WINDOWEVENT_MAXIMIZED as u8       => Maximized,
+            e if e == SDL_WINDOWEVENT_RESTORED as u8        => Restored,
+            e if e == SDL_WINDOWEVENT_ENTER as u8           => Enter,
+            e if e == SDL_WINDOWEVENT_LEAVE as u8           => Leave,
+            e if e == SDL_WINDOWEVENT_FOCUS_GAINED as u8    => FocusGained,
+            e if e == SDL_WINDOWEVENT_FOCUS_LOST as u8      => FocusLost,
+            e if e == SDL_WINDOWEVENT_CLOSE as u8           => Close,
+            e => UnknownWindowEvent { event_id: e, data1: data1, data2: data2 },
+        }
+    }
+
+    /// Returns the event id and data fields used by SDL for this change.
+    pub fn to_sdl(&self) -> (u8, i32, i32) {
+        match *self {
+            Shown                   => (SDL_WINDOWEVENT_SHOWN as u8, 0, 0),
+            Hidden                  => (SDL_WINDOWEVENT_HIDDEN as u8, 0, 0),
+            Exposed                 => (SDL_WINDOWEVENT_EXPOSED as u8, 0, 0),
+            Moved { x, y }          => (SDL_WINDOWEVENT_MOVED as u8, x, y),
+            Resized { w, h }        => (SDL_WINDOWEVENT_RESIZED as u8, w, h),
+            SizeChanged { w, h }    => (SDL_WINDOWEVENT_SIZE_CHANGED as u8, w, h),
+            Minimized               => (SDL_WINDOWEVENT_MINIMIZED as u8, 0, 0),
+            Maximized               => (SDL_WINDOWEVENT_MAXIMIZED as u8, 0, 0),
+            Restored                => (SDL_WINDOWEVENT_RESTORED as u8, 0, 0),
+            Enter                   => (SDL_WINDOWEVENT_ENTER as u8, 0, 0),
+            Leave                   => (SDL_WINDOWEVENT_LEAVE as u8, 0, 0),
+            FocusGained             => (SDL_WINDOWEVENT_FOCUS_GAINED as u8, 0, 0),
+            FocusLost               => (SDL_WINDOWEVENT_FOCUS_LOST as u8, 0, 0),
+            Close                   => (SDL_WINDOWEVENT_CLOSE as u8, 0, 0),
+            UnknownWindowEvent { event_id, data1, data2 } => (event_id, data1, data2),
+        }
+    }
+}
+
+/// A safe representation of an SDL event. Identifiers such as `window_id`,
+/// `which` and `touch_id` are passed through untouched from SDL.
+#[deriving(Clone, Eq)]
 pub enum Event {
+    /// The user requested that the application quit.
+    Quit,
+
+    /// The application is being terminated by the OS.
     AppTerminating,
+    /// The application is low on memory and should free what it can.
     AppLowMemory,
+    /// The application is about to enter the background.
     AppWillEnterBackground,
+    /// The application entered the background.
     AppDidEnterBackground,
+    /// The application is about to enter the foreground.
     AppWillEnterForeground,
+    /// The application entered the foreground and is now interactive.
     AppDidEnterForeground,
-    WindowEvent,
-    Keyboard,
-    TextEditing,
-    TextInput,
-    MouseMotion,
-    MouseButton,
-    MouseWheel,
-    JoyAxis,
-    JoyBall,
-    JoyHat,
-    JoyButton,
-    JoyDevice,
-    ControllerAxis,
-    ControllerButton,
-    ControllerDevice,
-    TouchFinger,
-    MultiGesture,
-    DollarGesture,
-    Drop,
-    Quit,
-    User,
-    SysWm,
+
+    /// The state of a window changed.
+    WindowEvent {
+        window_id:  u32,
+        kind:       WindowEventKind,
+    },
+    /// A platform specific window manager event.
+    SysWm {
+        msg:        *SDL_SysWMmsg,
+    },
+
+    /// A key was pressed or released. The keycode depends on the keyboard
+    /// layout. Keys that produce a character report its code point, which
+    /// is not always one of the `SDL_Keycode` values, so the keycode is
+    /// stored as a raw value that can be decoded with
+    /// `FromPrimitive::from_i32`.
+    Keyboard {
+        window_id:  u32,
+        state:      PressedState,
+        repeat:     bool,
+        scancode:   SDL_Scancode,
+        keycode:    i32,
+        keymod:     SDL_Keymod,
+    },
+    /// The text currently being composed by an input method changed.
+    TextEditing {
+        window_id:  u32,
+        text:       ~str,
+        start:      i32,
+        length:     i32,
+    },
+    /// Text was entered.
+    TextInput {
+        window_id:  u32,
+        text:       ~str,
+    },
+
+    /// The mouse moved. `state` is a bitmask of the buttons being held.
+    MouseMotion {
+        window_id:  u32,
+        which:      u32,
+        state:      u32,
+        x:          i32,
+        y:          i32,
+        xrel:       i32,
+        yrel:       i32,
+    },
+    /// A mouse button was pressed or released.
+    MouseButton {
+        window_id:  u32,
+        which:      u32,
+        button:     u8,
+        state:      PressedState,
+        x:          i32,
+        y:          i32,
+    },
+    /// The mouse wheel was scrolled.
+    MouseWheel {
+        window_id:  u32,
+        which:      u32,
+        x:          i32,
+        y:          i32,
+    },
+
+    /// A joystick axis moved.
+    JoyAxis {
+        which:      SDL_JoystickID,
+        axis:       u8,
+        value:      i16,
+    },
+    /// A joystick trackball moved.
+    JoyBall {
+        which:      SDL_JoystickID,
+        ball:       u8,
+        xrel:       i16,
+        yrel:       i16,
+    },
+    /// The position of a joystick hat changed.
+    JoyHat {
+        which:      SDL_JoystickID,
+        hat:        u8,
+        state:      SDL_Hat,
+    },
+    /// A joystick button was pressed or released.
+    JoyButton {
+        which:      SDL_JoystickID,
+        button:     u8,
+        state:      PressedState,
+    },
+    /// A joystick was attached or removed. When a device is added `which` is
+    /// the device index, otherwise it is the instance id.
+    JoyDevice {
+        which:      i32,
+        change:     JoyDeviceChange,
+    },
+
+    /// A game controller axis moved.
+    ControllerAxis {
+        which:      SDL_JoystickID,
+        axis:       u8,
+        value:      i16,
+    },
+    /// A game controller button was pressed or released.
+    ControllerButton {
+        which:      SDL_JoystickID,
+        button:     u8,
+        state:      PressedState,
+    },
+    /// A game controller was attached, removed or remapped. When a device is
+    /// added `which` is the device index, otherwise it is the instance id.
+    ControllerDevice {
+        which:      i32,
+        change:     DeviceChange,
+    },
+
+    /// A finger touched, left or moved across a touch device. Coordinates are
+    /// normalised to the range `0.0` to `1.0`.
+    TouchFinger {
+        touch_id:   SDL_TouchID,
+        finger_id:  SDL_FingerID,
+        change:     FingerChange,
+        x:          f32,
+        y:          f32,
+        dx:         f32,
+        dy:         f32,
+        pressure:   f32,
+    },
+    /// A gesture involving multiple fingers was performed.
+    MultiGesture {
+        touch_id:   SDL_TouchID,
+        d_theta:    f32,
+        d_dist:     f32,
+        x:          f32,
+        y:          f32,
+        num_fingers: u16,
+    },
+    /// A recorded dollar gesture was recognised.
+    DollarGesture {
+        touch_id:   SDL_TouchID,
+        gesture_id: SDL_GestureID,
+        num_fingers: u32,
+        error:      f32,
+        x:          f32,
+        y:          f32,
+    },
+    /// A dollar gesture was recorded with `SDL_RecordGesture`.
+    DollarRecord {
+        touch_id:   SDL_TouchID,
+        gesture_id: SDL_GestureID,
+    },
+
+    /// The contents of the clipboard changed.
+    ClipboardUpdate,
+
+    /// A file was dropped onto a window.
+    DropFile {
+        filename:   ~str,
+    },
+
+    /// An event registered by the application. `event_type` is in the range
+    /// `SDL_USEREVENT <= event_type < SDL_LASTEVENT`.
+    User {
+        event_type: u32,
+        window_id:  u32,
+        code:       i32,
+        data1:      *c_void,
+        data2:      *c_void,
+    },
+
+    /// An event of a type registered with `Sdl::register_custom_event`,
+    /// carrying the value that it was pushed with.
+    Custom {
+        event_type: u32,
+        payload:    CustomPayload,
+    },
+
+    /// An event type that this version of the bindings does not recognise,
+    /// or an event from an `EventSender` that has not yet been taken off the
+    /// queue.
+    Unknown {
+        event_type: u32,
+    },
 }
 
 impl Event {
-    fn from_sdl_event_data(event_type: Uint32, data: SDL_Event) -> Event {
+    /// Converts a raw SDL event into its safe representation. The event is
+    /// only read from, so any memory it refers to is still owned by the
+    /// caller.
+    pub fn from_sdl_event(raw: &SDL_Event) -> Event {
+        unsafe {
+            match raw.event_type {
+                t if t == SDL_QUIT                      => Quit,
+                t if t == SDL_APP_TERMINATING           => AppTerminating,
+                t if t == SDL_APP_LOWMEMORY             => AppLowMemory,
+                t if t == SDL_APP_WILLENTERBACKGROUND   => AppWillEnterBackground,
+                t if t == SDL_APP_DIDENTERBACKGROUND    => AppDidEnterBackground,
+                t if t == SDL_APP_WILLENTERFOREGROUND   => AppWillEnterForeground,
+                t if t == SDL_APP_DIDENTERFOREGROUND    => AppDidEnterForeground,
+                t if t.is_window()                      => Event::from_sdl_window_event(raw.window()),
+                t if t.is_syswm()                       => Event::from_sdl_syswm(raw.syswm()),
+                t if t.is_key()                         => Event::from_sdl_keyboard(raw.key()),
+                t if t.is_edit()                        => Event::from_sdl_text_editing(raw.edit()),
+                t if t.is_text()                        => Event::from_sdl_text_input(raw.text()),
+                t if t.is_motion()                      => Event::from_sdl_mouse_motion(raw.motion()),
+                t if t.is_button()                      => Event::from_sdl_mouse_button(raw.button()),
+                t if t.is_wheel()                       => Event::from_sdl_mouse_wheel(raw.wheel()),
+                t if t.is_jaxis()                       => Event::from_sdl_joy_axis(raw.jaxis()),
+                t if t.is_jball()                       => Event::from_sdl_joy_ball(raw.jball()),
+                t if t.is_jhat()                        => Event::from_sdl_joy_hat(raw.jhat()),
+                t if t.is_jbutton()                     => Event::from_sdl_joy_button(raw.jbutton()),
+                t if t.is_jdevice()                     => Event::from_sdl_joy_device(raw.jdevice()),
+                t if t.is_caxis()                       => Event::from_sdl_controller_axis(raw.caxis()),
+                t if t.is_cbutton()                     => Event::from_sdl_controller_button(raw.cbutton()),
+                t if t.is_cdevice()                     => Event::from_sdl_controller_device(raw.cdevice()),
+                t if t.is_tfinger()                     => Event::from_sdl_touch_finger(raw.tfinger()),
+                t if t.is_mgesture()                    => Event::from_sdl_multi_gesture(raw.mgesture()),
+                t if t.is_dgesture()                    => Event::from_sdl_dollar_gesture(raw.dgesture()),
+                t if t == SDL_DOLLARRECORD              => Event::from_sdl_dollar_record(raw.dgesture()),
+                t if t == SDL_CLIPBOARDUPDATE           => ClipboardUpdate,
+                t if t.is_drop_()                       => Event::from_sdl_drop(raw.drop_()),
+                t if t.is_user()                        => Event::from_sdl_user(raw.user()),
+                t                                       => Unknown { event_type: t.bits() },
+            }
+        }
     }
 
-    fn from_sdl_window_event(data: SDL_WindowEvent) -> Event {
-         WindowEvent
+    fn from_sdl_window_event(data: &SDL_WindowEvent) -> Event {
+        WindowEvent {
+            window_id:  data.windowID,
+            kind:       WindowEventKind::from_sdl(data.event, data.data1, data.data2),
+        }
     }
 
-    fn from_sdl_keyboard(data: SDL_KeyboardEvent) -> Event {
-         Keyboard
+    fn from_sdl_syswm(data: &SDL_SysWMEvent) -> Event {
+        SysWm { msg: data.msg }
     }
 
-    fn from_sdl_text_editing(data: SDL_TextEditingEvent) -> Event {
-         TextEditing
+    fn from_sdl_keyboard(data: &SDL_KeyboardEvent) -> Event {
+        Keyboard {
+            window_id:  data.windowID,
+            state:      PressedState::from_sdl(data.state),
+            repeat:     data.repeat != 0,
+            scancode:   data.keysym.scancode,
+            keycode:    data.keysym.sym,
+            keymod:     SDL_Keymod::from_bits_truncate(data.keysym.key_mod),
+        }
     }
 
-    fn from_sdl_text_input(data: SDL_TextInputEvent) -> Event {
-         TextInput
+    fn from_sdl_text_editing(data: &SDL_TextEditingEvent) -> Event {
+        TextEditing {
+            window_id:  data.windowID,
+            text:       unsafe { from_c_str(data.text.as_ptr()) },
+            start:      data.start,
+            length:     data.length,
+        }
     }
 
-    fn from_sdl_mouse_motion(data: SDL_MouseMotionEvent) -> Event {
-         MouseMotion
+    fn from_sdl_text_input(data: &SDL_TextInputEvent) -> Event {
+        TextInput {
+            window_id:  data.windowID,
+            text:       unsafe { from_c_str(data.text.as_ptr()) },
+        }
     }
 
-    fn from_sdl_mouse_button(data: SDL_MouseButtonEvent) -> Event {
-         MouseButton
+    fn from_sdl_mouse_motion(data: &SDL_MouseMotionEvent) -> Event {
+        MouseMotion {
+            window_id:  data.windowID,
+            which:      data.which,
+            state:      data.state,
+            x:          data.x,
+            y:          data.y,
+            xrel:       data.xrel,
+            yrel:       data.yrel,
+        }
     }
 
-    fn from_sdl_mouse_wheel(data: SDL_MouseWheelEvent) -> Event {
-         MouseWheel
+    fn from_sdl_mouse_button(data: &SDL_MouseButtonEvent) -> Event {
+        MouseButton {
+            window_id:  data.windowID,
+            which:      data.which,
+            button:     data.button,
+            state:      PressedState::from_sdl(data.state),
+            x:          data.x,
+            y:          data.y,
+        }
     }
 
-    fn from_sdl_joy_axis(data: SDL_JoyAxisEvent) -> Event {
-         JoyAxis
+    fn from_sdl_mouse_wheel(data: &SDL_MouseWheelEvent) -> Event {
+        MouseWheel {
+            window_id:  data.windowID,
+            which:      data.which,
+            x:          data.x,
+            y:          data.y,
+        }
     }
 
-    fn from_sdl_joy_ball(data: SDL_JoyBallEvent) -> Event {
-         JoyBall
+    fn from_sdl_joy_axis(data: &SDL_JoyAxisEvent) -> Event {
+        JoyAxis {
+            which:      data.which,
+            axis:       data.axis,
+            value:      data.value,
+        }
     }
 
-    fn from_sdl_joy_hat(data: SDL_JoyHatEvent) -> Event {
-         JoyHat
+    fn from_sdl_joy_ball(data: &SDL_JoyBallEvent) -> Event {
+        JoyBall {
+            which:      data.which,
+            ball:       data.ball,
+            xrel:       data.xrel,
+            yrel:       data.yrel,
+        }
     }
 
-    fn from_sdl_joy_button(data: SDL_JoyButtonEvent) -> Event {
-         JoyButton
+    fn from_sdl_joy_hat(data: &SDL_JoyHatEvent) -> Event {
+        JoyHat {
+            which:      data.which,
+            hat:        data.hat,
+            state:      SDL_Hat::from_bits_truncate(data.value),
+        }
     }
 
-    fn from_sdl_joy_device(data: SDL_JoyDeviceEvent) -> Event {
-         JoyDevice
+    fn from_sdl_joy_button(data: &SDL_JoyButtonEvent) -> Event {
+        JoyButton {
+            which:      data.which,
+            button:     data.button,
+            state:      PressedState::from_sdl(data.state),
+        }
     }
 
-    fn from_sdl_controller_axis(data: SDL_ControllerAxisEvent) -> Event {
-         ControllerAxis
+    fn from_sdl_joy_device(data: &SDL_JoyDeviceEvent) -> Event {
+        JoyDevice {
+            which:      data.which,
+            change:     if data.event_type == SDL_JOYDEVICEADDED { JoyAdded }
+                        else { JoyRemoved },
+        }
     }
 
-    fn from_sdl_controller_button(data: SDL_ControllerButtonEvent) -> Event {
-         ControllerButton
+    fn from_sdl_controller_axis(data: &SDL_ControllerAxisEvent) -> Event {
+        ControllerAxis {
+            which:      data.which,
+            axis:       data.axis,
+            value:      data.value,
+        }
     }
 
-    fn from_sdl_controller_device(data: SDL_ControllerDeviceEvent) -> Event {
-         ControllerDevice
+    fn from_sdl_controller_button(data: &SDL_ControllerButtonEvent) -> Event {
+        ControllerButton {
+            which:      data.which,
+            button:     data.button,
+            state:      PressedState::from_sdl(data.state),
+        }
     }
 
-    fn from_sdl_touch_finger(data: SDL_TouchFingerEvent) -> Event {
-         TouchFinger
+    fn from_sdl_controller_device(data: &SDL_ControllerDeviceEvent) -> Event {
+        ControllerDevice {
+            which:      data.which,
+            change:     match data.event_type {
+                t if t == SDL_CONTROLLERDEVICEADDED     => DeviceAdded,
+                t if t == SDL_CONTROLLERDEVICEREMOVED   => DeviceRemoved,
+                _                                       => DeviceRemapped,
+            },
+        }
     }
 
-    fn from_sdl_multi_gesture(data: SDL_MultiGestureEvent) -> Event {
-         MultiGesture
+    fn from_sdl_touch_finger(data: &SDL_TouchFingerEvent) -> Event {
+        TouchFinger {
+            touch_id:   data.touchId,
+            finger_id:  data.fingerId,
+            change:     match data.event_type {
+                t if t == SDL_FINGERDOWN    => FingerDown,
+                t if t == SDL_FINGERUP      => FingerUp,
+                _                           => FingerMotion,
+            },
+            x:          data.x,
+            y:          data.y,
+            dx:         data.dx,
+            dy:         data.dy,
+            pressure:   data.pressure,
+        }
     }
 
-    fn from_sdl_dollar_gesture(data: SDL_DollarGestureEvent) -> Event {
-         DollarGesture
+    fn from_sdl_multi_gesture(data: &SDL_MultiGestureEvent) -> Event {
+        MultiGesture {
+            touch_id:   data.touchId,
+            d_theta:    data.dTheta,
+            d_dist:     data.dDist,
+            x:          data.x,
+            y:          data.y,
+            num_fingers: data.numFingers,
+        }
     }
 
-    fn from_sdl_drop(data: SDL_DropEvent) -> Event {
-         Drop
+    fn from_sdl_dollar_gesture(data: &SDL_DollarGestureEvent) -> Event {
+        DollarGesture {
+            touch_id:   data.touchId,
+            gesture_id: data.gestureId,
+            num_fingers: data.numFingers,
+            error:      data.error,
+            x:          data.x,
+            y:          data.y,
+        }
     }
 
-    fn from_sdl_user(data: SDL_UserEvent) -> Event {
-         User
+    fn from_sdl_dollar_record(data: &SDL_DollarGestureEvent) -> Event {
+        DollarRecord {
+            touch_id:   data.touchId,
+            gesture_id: data.gestureId,
+        }
     }
 
-    fn from_sdl_syswm(data: SDL_SysWMEvent) -> Event {
-         SysWM
+    fn from_sdl_drop(data: &SDL_DropEvent) -> Event {
+        DropFile { filename: unsafe { from_c_str(data.file) } }
+    }
+
+    fn from_sdl_user(data: &SDL_UserEvent) -> Event {
+        if data.data2 == custom_payload_marker() {
+            // The event still owns its box, so only the handle is cloned
+            let value: &Rc<~Any:Send> = unsafe { transmute(data.data1) };
+            return Custom {
+                event_type: data.event_type.bits(),
+                payload:    CustomPayload { value: value.clone() },
+            };
+        }
+        if data.data2 == sent_payload_marker() {
+            // Only `make_event` may take over the box of a sent value, so an
+            // event that is still queued must not expose it
+            return Unknown { event_type: data.event_type.bits() };
+        }
+        User {
+            event_type: data.event_type.bits(),
+            window_id:  data.windowID,
+            code:       data.code,
+            data1:      data.data1,
+            data2:      data.data2,
+        }
     }
 }
 
-fn make_event(SDL_Event { timestamp, event_type, data }: SDL_Event) -> EventStamped {
-    static LAST: Uint32 = SDL_LASTEVENT - 1;
-    let event = match event_type {
-        SDL_QUIT                        => Quit,
-        SDL_APP_TERMINATING             => AppTerminating,
-        SDL_APP_LOWMEMORY               => AppLowMemory,
-        SDL_APP_WILLENTERBACKGROUND     => AppWillEnterBackground,
-        SDL_APP_DIDENTERBACKGROUND      => AppDidEnterBackground,
-        SDL_APP_WILLENTERFOREGROUND     => AppWillEnterForeground,
-        SDL_APP_DIDENTERFOREGROUND      => AppDidEnterForeground,
-        SDL_WINDOWEVENT                 => WindowEvent,
-        SDL_KEYDOWN                     => Keyboard,
-        SDL_KEYUP                       => Keyboard,
-        SDL_TEXTEDITING                 => TextEditing,
-        SDL_TEXTINPUT                   => TextInput,
-        SDL_MOUSEMOTION                 => MouseMotion,
-        SDL_MOUSEBUTTONDOWN             => MouseButton,
-        SDL_MOUSEBUTTONUP               => MouseButton,
-        SDL_MOUSEWHEEL                  => MouseWheel,
-        SDL_JOYAXISMOTION               => JoyAxis,
-        SDL_JOYBALLMOTION               => JoyBall,
-        SDL_JOYHATMOTION                => JoyHat,
-        SDL_JOYBUTTONDOWN               => JoyButton,
-        SDL_JOYBUTTONUP                 => JoyButton,
-        SDL_JOYDEVICEADDED              => JoyDevice,
-        SDL_JOYDEVICEREMOVED            => JoyDevice,
-        SDL_CONTROLLERAXISMOTION        => ControllerAxis,
-        SDL_CONTROLLERBUTTONDOWN        => ControllerButton,
-        SDL_CONTROLLERBUTTONUP          => ControllerButton,
-        SDL_CONTROLLERDEVICEADDED       => ControllerDevice,
-        SDL_CONTROLLERDEVICEREMOVED     => ControllerDevice,
-        SDL_CONTROLLERDEVICEREMAPPED    => ControllerDevice,
-        SDL_FINGERDOWN                  => TouchFinger,
-        SDL_FINGERUP                    => TouchFinger,
-        SDL_FINGERMOTION                => TouchFinger,
-        SDL_DOLLARGESTURE               => DollarGesture,
-        // SDL_DOLLARRECORD                => ...,
-        SDL_MULTIGESTURE                => MultiGesture,
-        SDL_CLIPBOARDUPDATE             => CLIPBOARDUPDATE,
-        SDL_DROPFILE                    => Drop,
-        SDL_USEREVENT .. LAST           => UserEvent,
-        _                               => unreachable!(),
-    };
-    (timestamp, event)
+impl Event {
+    /// Returns the type of the event. The type of a `User` or `Custom` event
+    /// outside the range of user events is truncated, as for `Unknown`.
+    pub fn event_type(&self) -> EventType {
+        match *self {
+            Quit                        => SDL_QUIT,
+            AppTerminating              => SDL_APP_TERMINATING,
+            AppLowMemory                => SDL_APP_LOWMEMORY,
+            AppWillEnterBackground      => SDL_APP_WILLENTERBACKGROUND,
+            AppDidEnterBackground       => SDL_APP_DIDENTERBACKGROUND,
+            AppWillEnterForeground      => SDL_APP_WILLENTERFOREGROUND,
+            AppDidEnterForeground       => SDL_APP_DIDENTERFOREGROUND,
+            WindowEvent { .. }          => SDL_WINDOWEVENT,
+            SysWm { .. }                => SDL_SYSWMEVENT,
+            Keyboard { state: Pressed, .. }         => SDL_KEYDOWN,
+            Keyboard { state: Released, .. }        => SDL_KEYUP,
+            TextEditing { .. }          => SDL_TEXTEDITING,
+            TextInput { .. }            => SDL_TEXTINPUT,
+            MouseMotion { .. }          => SDL_MOUSEMOTION,
+            MouseButton { state: Pressed, .. }      => SDL_MOUSEBUTTONDOWN,
+            MouseButton { state: Released, .. }     => SDL_MOUSEBUTTONUP,
+            MouseWheel { .. }           => SDL_MOUSEWHEEL,
+            JoyAxis { .. }              => SDL_JOYAXISMOTION,
+            JoyBall { .. }              => SDL_JOYBALLMOTION,
+            JoyHat { .. }               => SDL_JOYHATMOTION,
+            JoyButton { state: Pressed, .. }        => SDL_JOYBUTTONDOWN,
+            JoyButton { state: Released, .. }       => SDL_JOYBUTTONUP,
+            JoyDevice { change: JoyAdded, .. }      => SDL_JOYDEVICEADDED,
+            JoyDevice { change: JoyRemoved, .. }    => SDL_JOYDEVICEREMOVED,
+            ControllerAxis { .. }       => SDL_CONTROLLERAXISMOTION,
+            ControllerButton { state: Pressed, .. }     => SDL_CONTROLLERBUTTONDOWN,
+            ControllerButton { state: Released, .. }    => SDL_CONTROLLERBUTTONUP,
+            ControllerDevice { change: DeviceAdded, .. }    => SDL_CONTROLLERDEVICEADDED,
+            ControllerDevice { change: DeviceRemoved, .. }  => SDL_CONTROLLERDEVICEREMOVED,
+            ControllerDevice { change: DeviceRemapped, .. } => SDL_CONTROLLERDEVICEREMAPPED,
+            TouchFinger { change: FingerDown, .. }      => SDL_FINGERDOWN,
+            TouchFinger { change: FingerUp, .. }        => SDL_FINGERUP,
+            TouchFinger { change: FingerMotion, .. }    => SDL_FINGERMOTION,
+            MultiGesture { .. }         => SDL_MULTIGESTURE,
+            DollarGesture { .. }        => SDL_DOLLARGESTURE,
+            DollarRecord { .. }         => SDL_DOLLARRECORD,
+            ClipboardUpdate             => SDL_CLIPBOARDUPDATE,
+            DropFile { .. }             => SDL_DROPFILE,
+            User { event_type, .. }     => SDL_EventType::from_bits_truncate(event_type),
+            Custom { event_type, .. }   => SDL_EventType::from_bits_truncate(event_type),
+            Unknown { event_type }      => SDL_EventType::from_bits_truncate(event_type),
+        }
+    }
+
+    /// Returns the id of the window the event was reported for, if any. An
+    /// id of zero means that no window had focus.
+    pub fn window_id(&self) -> Option<u32> {
+        match *self {
+            WindowEvent { window_id, .. }   => Some(window_id),
+            Keyboard { window_id, .. }      => Some(window_id),
+            TextEditing { window_id, .. }   => Some(window_id),
+            TextInput { window_id, .. }     => Some(window_id),
+            MouseMotion { window_id, .. }   => Some(window_id),
+            MouseButton { window_id, .. }   => Some(window_id),
+            MouseWheel { window_id, .. }    => Some(window_id),
+            User { window_id, .. }          => Some(window_id),
+            _                               => None,
+        }
+    }
+
+    /// Converts the event back into a raw SDL event with a zero timestamp.
+    /// Fails if text does not fit in the fixed size buffers of the SDL event
+    /// structures, if text or a filename contains a nul character, or if the
+    /// type of a `User` or `Custom` event is outside the range of user
+    /// events. The filename of a `DropFile` event is copied
+    /// into memory allocated by SDL, which must be released with
+    /// `free_sdl_event` unless ownership is handed over to the queue.
+    pub fn to_sdl_event(&self) -> SdlResult<SDL_Event> {
+        Ok(unsafe {
+            match *self {
+                Quit                    => SDL_Event::new(SDL_QUIT),
+                AppTerminating          => SDL_Event::new(SDL_APP_TERMINATING),
+                AppLowMemory            => SDL_Event::new(SDL_APP_LOWMEMORY),
+                AppWillEnterBackground  => SDL_Event::new(SDL_APP_WILLENTERBACKGROUND),
+                AppDidEnterBackground   => SDL_Event::new(SDL_APP_DIDENTERBACKGROUND),
+                AppWillEnterForeground  => SDL_Event::new(SDL_APP_WILLENTERFOREGROUND),
+                AppDidEnterForeground   => SDL_Event::new(SDL_APP_DIDENTERFOREGROUND),
+                ClipboardUpdate         => SDL_Event::new(SDL_CLIPBOARDUPDATE),
+
+                WindowEvent { window_id, kind } => {
+                    let (event_id, data1, data2) = kind.to_sdl();
+                    let mut raw = SDL_Event::new(SDL_WINDOWEVENT);
+                    {
+                        let data = raw.window_mut();
+                        data.windowID = window_id;
+                        data.event = event_id;
+                        data.data1 = data1;
+                        data.data2 = data2;
+                    }
+                    raw
+                },
+                SysWm { msg } => {
+                    let mut raw = SDL_Event::new(SDL_SYSWMEVENT);
+                    raw.syswm_mut().msg = msg;
+                    raw
+                },
+                Keyboard { window_id, state, repeat, scancode, keycode, keymod } => {
+                    let mut raw = SDL_Event::new(match state {
+                        Pressed  => SDL_KEYDOWN,
+                        Released => SDL_KEYUP,
+                    });
+                    {
+                        let data = raw.key_mut();
+                        data.windowID = window_id;
+                        data.state = state.to_sdl();
+                        data.repeat = repeat as u8;
+                        data.keysym.scancode = scancode;
+                        data.keysym.sym = keycode;
+                        data.keysym.key_mod = keymod.bits();
+                    }
+                    raw
+                },
+                TextEditing { window_id, ref text, start, length } => {
+                    let mut raw = SDL_Event::new(SDL_TEXTEDITING);
+                    {
+                        let data = raw.edit_mut();
+                        data.windowID = window_id;
+                        try!(copy_text(text.as_slice(), &mut data.text));
+                        data.start = start;
+                        data.length = length;
+                    }
+                    raw
+                },
+                TextInput { window_id, ref text } => {
+                    let mut raw = SDL_Event::new(SDL_TEXTINPUT);
+                    {
+                        let data = raw.text_mut();
+                        data.windowID = window_id;
+                        try!(copy_text(text.as_slice(), &mut data.text));
+                    }
+                    raw
+                },
+                MouseMotion { window_id, which, state, x, y, xrel, yrel } => {
+                    let mut raw = SDL_Event::new(SDL_MOUSEMOTION);
+                    {
+                        let data = raw.motion_mut();
+                        data.windowID = window_id;
+                        data.which = which;
+                        data.state = state;
+                        data.x = x;
+                        data.y = y;
+                        data.xrel = xrel;
+                        data.yrel = yrel;
+                    }
+                    raw
+                },
+                MouseButton { window_id, which, button, state, x, y } => {
+                    let mut raw = SDL_Event::new(match state {
+                        Pressed  => SDL_MOUSEBUTTONDOWN,
+                        Released => SDL_MOUSEBUTTONUP,
+                    });
+                    {
+                        let data = raw.button_mut();
+                        data.windowID = window_id;
+                        data.which = which;
+                        data.button = button;
+                        data.state = state.to_sdl();
+                        data.x = x;
+                        data.y = y;
+                    }
+                    raw
+                },
+                MouseWheel { window_id, which, x, y } => {
+                    let mut raw = SDL_Event::new(SDL_MOUSEWHEEL);
+                    {
+                        let data = raw.wheel_mut();
+                        data.windowID = window_id;
+                        data.which = which;
+                        data.x = x;
+                        data.y = y;
+                    }
+                    raw
+                },
+                JoyAxis { which, axis, value } => {
+                    let mut raw = SDL_Event::new(SDL_JOYAXISMOTION);
+                    {
+                        let data = raw.jaxis_mut();
+                        data.which = which;
+                        data.axis = axis;
+                        data.value = value;
+                    }
+                    raw
+                },
+                JoyBall { which, ball, xrel, yrel } => {
+                    let mut raw = SDL_Event::new(SDL_JOYBALLMOTION);
+                    {
+                        let data = raw.jball_mut();
+                        data.which = which;
+                        data.ball = ball;
+                        data.xrel = xrel;
+                        data.yrel = yrel;
+                    }
+                    raw
+                },
+                JoyHat { which, hat, state } => {
+                    let mut raw = SDL_Event::new(SDL_JOYHATMOTION);
+                    {
+                        let data = raw.jhat_mut();
+                        data.which = which;
+                        data.hat = hat;
+                        data.value = state.bits();
+                    }
+                    raw
+                },
+                JoyButton { which, button, state } => {
+                    let mut raw = SDL_Event::new(match state {
+                        Pressed  => SDL_JOYBUTTONDOWN,
+                        Released => SDL_JOYBUTTONUP,
+                    });
+                    {
+                        let data = raw.jbutton_mut();
+                        data.which = which;
+                        data.button = button;
+                        data.state = state.to_sdl();
+                    }
+                    raw
+                },
+                JoyDevice { which, change } => {
+                    let mut raw = SDL_Event::new(match change {
+                        JoyAdded        => SDL_JOYDEVICEADDED,
+                        JoyRemoved      => SDL_JOYDEVICEREMOVED,
+                    });
+                    raw.jdevice_mut().which = which;
+                    raw
+                },
+                ControllerAxis { which, axis, value } => {
+                    let mut raw = SDL_Event::new(SDL_CONTROLLERAXISMOTION);
+                    {
+                        let data = raw.caxis_mut();
+                        data.which = which;
+                        data.axis = axis;
+                        data.value = value;
+                    }
+                    raw
+                },
+                ControllerButton { which, button, state } => {
+                    let mut raw = SDL_Event::new(match state {
+                        Pressed  => SDL_CONTROLLERBUTTONDOWN,
+                        Released => SDL_CONTROLLERBUTTONUP,
+                    });
+                    {
+                        let data = raw.cbutton_mut();
+                        data.which = which;
+                        data.button = button;
+                        data.state = state.to_sdl();
+                    }
+                    raw
+                },
+                ControllerDevice { which, change } => {
+                    let mut raw = SDL_Event::new(match change {
+                        DeviceAdded     => SDL_CONTROLLERDEVICEADDED,
+                        DeviceRemoved   => SDL_CONTROLLERDEVICEREMOVED,
+                        DeviceRemapped  => SDL_CONTROLLERDEVICEREMAPPED,
+                    });
+                    raw.cdevice_mut().which = which;
+                    raw
+                },
+                TouchFinger { touch_id, finger_id, change, x, y, dx, dy, pressure } => {
+                    let mut raw = SDL_Event::new(match change {
+                        FingerDown      => SDL_FINGERDOWN,
+                        FingerUp        => SDL_FINGERUP,
+                        FingerMotion    => SDL_FINGERMOTION,
+                    });
+                    {
+                        let data = raw.tfinger_mut();
+                        data.touchId = touch_id;
+                        data.fingerId = finger_id;
+                        data.x = x;
+                        data.y = y;
+                        data.dx = dx;
+                        data.dy = dy;
+                        data.pressure = pressure;
+                    }
+                    raw
+                },
+                MultiGesture { touch_id, d_theta, d_dist, x, y, num_fingers } => {
+                    let mut raw = SDL_Event::new(SDL_MULTIGESTURE);
+                    {
+                        let data = raw.mgesture_mut();
+                        data.touchId = touch_id;
+                        data.dTheta = d_theta;
+                        data.dDist = d_dist;
+                        data.x = x;
+                        data.y = y;
+                        data.numFingers = num_fingers;
+                    }
+                    raw
+                },
+                DollarGesture { touch_id, gesture_id, num_fingers, error, x, y } => {
+                    let mut raw = SDL_Event::new(SDL_DOLLARGESTURE);
+                    {
+                        let data = raw.dgesture_mut();
+                        data.touchId = touch_id;
+                        data.gestureId = gesture_id;
+                        data.numFingers = num_fingers;
+                        data.error = error;
+                        data.x = x;
+                        data.y = y;
+                    }
+                    raw
+                },
+                DollarRecord { touch_id, gesture_id } => {
+                    let mut raw = SDL_Event::new(SDL_DOLLARRECORD);
+                    {
+                        let data = raw.dgesture_mut();
+                        data.touchId = touch_id;
+                        data.gestureId = gesture_id;
+                    }
+                    raw
+                },
+                DropFile { ref filename } => {
+                    try!(check_no_nul(filename.as_slice()));
+                    let len = filename.len();
+                    let file = SDL_malloc((len + 1) as size_t) as *mut u8;
+                    ptr::copy_nonoverlapping_memory(file, filename.as_ptr(), len);
+                    *file.offset(len as int) = 0;
+
+                    let mut raw = SDL_Event::new(SDL_DROPFILE);
+                    raw.drop_mut().file = file as *c_char;
+                    raw
+                },
+                User { event_type, window_id, code, data1, data2 } => {
+                    let mut raw = SDL_Event::new(try!(user_event_type(event_type)));
+                    {
+                        let data = raw.user_mut();
+                        data.windowID = window_id;
+                        data.code = code;
+                        data.data1 = data1;
+                        data.data2 = data2;
+                    }
+                    raw
+                },
+                Custom { event_type, ref payload } => {
+                    let value: ~Rc<~Any:Send> = ~payload.value.clone();
+                    let mut raw = SDL_Event::new(try!(user_event_type(event_type)));
+                    {
+                        let data = raw.user_mut();
+                        data.data1 = transmute(value);
+                        data.data2 = custom_payload_marker();
+                    }
+                    raw
+                },
+                Unknown { event_type } => {
+                    SDL_Event::new(SDL_EventType::from_bits_truncate(event_type))
+                },
+            }
+        })
+    }
+}
+
+/// Checks that `event_type` is in the range reserved for user events.
+fn user_event_type(event_type: u32) -> SdlResult<SDL_EventType> {
+    if event_type < SDL_USEREVENT.bits() || event_type >= SDL_LASTEVENT.bits() {
+        return Err(format!("event type {:X} is not a user event type", event_type));
+    }
+    Ok(SDL_EventType::new_user_event(event_type))
+}
+
+/// Fails if `text` contains a nul character, which would cut it short when
+/// read back as a C string.
+fn check_no_nul(text: &str) -> SdlResult<()> {
+    if text.contains_char('\0') {
+        Err(format!("text containing a nul character cannot be sent in an event: {:?}", text))
+    } else {
+        Ok(())
+    }
+}
+
+/// Copies `text` into `buf` followed by a nul terminator, failing if it does
+/// not fit or contains a nul character.
+fn copy_text(text: &str, buf: &mut [c_char]) -> SdlResult<()> {
+    try!(check_no_nul(text));
+    let len = text.len();
+    if len >= buf.len() {
+        return Err(format!("text of {} bytes does not fit in an event, which holds at most {}",
+                           len, buf.len() - 1));
+    }
+    for (dst, &src) in buf.mut_iter().zip(text.as_bytes().iter()) {
+        *dst = src as c_char;
+    }
+    buf[len] = 0;
+    Ok(())
+}
+
+/// Releases any memory referred to by a raw event that was allocated for the
+/// receiver of the event. This must be called on events that were taken off
+/// the queue or built by `Event::to_sdl_event` and never handed over to SDL.
+pub unsafe fn free_sdl_event(raw: &SDL_Event) {
+    if raw.event_type.is_drop_() {
+        SDL_free(raw.drop_().file as *c_void)
+    } else if raw.event_type.is_user() && raw.user().data2 == custom_payload_marker() {
+        let _: ~Rc<~Any:Send> = transmute(raw.user().data1);
+    } else if raw.event_type.is_user() && raw.user().data2 == sent_payload_marker() {
+        let _: ~~Any:Send = transmute(raw.user().data1);
+    }
+}
+
+/// Returns `true` if the event was pushed by an `EventSender`.
+fn is_sent_event(raw: &SDL_Event) -> bool {
+    raw.event_type.is_user() && unsafe { raw.user().data2 } == sent_payload_marker()
+}
+
+/// Converts an event taken off the queue, releasing any memory that SDL
+/// handed over to the receiver of the event.
+pub fn make_event(raw: SDL_Event) -> EventStamped {
+    if is_sent_event(&raw) {
+        // Values pushed by an `EventSender` can only be shared once they have
+        // reached the receiving task, so the box is taken over here.
+        let value: ~~Any:Send = unsafe { transmute(raw.user().data1) };
+        let event = Custom {
+            event_type: raw.event_type.bits(),
+            payload:    CustomPayload { value: Rc::new(*value) },
+        };
+        return (raw.timestamp, event);
+    }
+    let event = Event::from_sdl_event(&raw);
+    unsafe { free_sdl_event(&raw) };
+    (raw.timestamp, event)
+}
+
+/// Adds a raw event to the back of the queue, returning `false` if it was
+/// dropped by the event filter. The queue takes ownership of the event on
+/// success, otherwise it is released with `free_sdl_event`.
+pub fn push_sdl_event(raw: SDL_Event) -> SdlResult<bool> {
+    match unsafe { SDL_PushEvent(&raw) } {
+        1 => Ok(true),
+        result => {
+            unsafe { free_sdl_event(&raw) };
+            if result == 0 { Ok(false) } else { Err(get_error()) }
+        }
+    }
+}
+
+/// Takes every event off the queue, releasing the memory they own.
+fn discard_queued_events() {
+    let mut raw = SDL_Event::new(SDL_FIRSTEVENT);
+    while unsafe { SDL_PeepEvents(&mut raw, 1, SDL_GETEVENT, SDL_FIRSTEVENT, SDL_LASTEVENT) } == 1 {
+        unsafe { free_sdl_event(&raw) };
+        raw = SDL_Event::new(SDL_FIRSTEVENT);
+    }
+}
+
+/// A closure that is handed events as they are added to the queue. The
+/// boxed callback is passed to SDL as the `userdata` of the filter.
+struct EventCallback<'a> {
+    callback: |&Event|: 'a -> bool,
+    // The closure is not `Send`, so it may only run on the thread that
+    // created it
+    thread: SDL_threadID,
+    // Set while the closure runs, in case it adds events itself
+    running: Cell<bool>,
+}
+
+impl<'a> EventCallback<'a> {
+    fn new(callback: |&Event|: 'a -> bool) -> ~EventCallback<'a> {
+        ~EventCallback {
+            callback: callback,
+            thread: unsafe { SDL_ThreadID() },
+            running: Cell::new(false),
+        }
+    }
+
+    fn as_userdata(&self) -> *c_void {
+        self as *EventCallback<'a> as *c_void
+    }
+}
+
+extern "C" fn event_callback(userdata: *c_void, raw: *SDL_Event) -> c_int {
+    // SDL calls filters and watches on the thread that adds the event, where
+    // the closure must not be called unless it is the one that created it.
+    // Events from an `EventSender` are also skipped on the creating thread,
+    // as their value cannot be shared yet.
+    let callback: &mut EventCallback = unsafe { transmute(userdata) };
+    if unsafe { SDL_ThreadID() } != callback.thread { return 1 }
+    if is_sent_event(unsafe { &*raw }) { return 1 }
+    // Events added by the closure are let through without calling it again,
+    // as the closure is already borrowed
+    if callback.running.get() { return 1 }
+    let event = Event::from_sdl_event(unsafe { &*raw });
+    callback.running.set(true);
+    let keep = (callback.callback)(&event);
+    callback.running.set(false);
+    keep as c_int
+}
+
+extern "C" fn discarding_event_callback(userdata: *c_void, raw: *SDL_Event) -> c_int {
+    let keep = event_callback(userdata, raw);
+    // SDL throws away the events that `SDL_FilterEvents` removes from the
+    // queue, so the memory they own has to be released here.
+    if keep == 0 { unsafe { free_sdl_event(&*raw) } }
+    keep
+}
+
+/// An event filter set with `Sdl::set_event_filter`. The filter is removed
+/// when this is dropped, unless another filter has replaced it since.
+///
+/// SDL empties the queue whenever the filter changes, so any pending events
+/// are discarded both when the filter is set and when it is removed.
+pub struct EventFilter<'a> {
+    callback: ~EventCallback<'a>,
+}
+
+impl<'a> EventFilter<'a> {
+    /// Sets the closure that decides whether each event is added to the
+    /// queue, replacing any previous filter. This is usually called through
+    /// `Sdl::set_event_filter`.
+    pub fn new(_sdl: &Sdl, callback: |&Event|: 'a -> bool) -> EventFilter<'a> {
+        let filter = EventFilter { callback: EventCallback::new(callback) };
+        // SDL would discard the queued events without releasing their memory
+        discard_queued_events();
+        unsafe { SDL_SetEventFilter(Some(event_callback), filter.callback.as_userdata()) };
+        filter
+    }
+}
+
+#[unsafe_destructor]
+impl<'a> Drop for EventFilter<'a> {
+    fn drop(&mut self) {
+        let mut current = None;
+        let mut userdata = ptr::null();
+        unsafe {
+            if SDL_GetEventFilter(&mut current, &mut userdata).to_bool()
+            && userdata == self.callback.as_userdata() {
+                discard_queued_events();
+                SDL_SetEventFilter(None, ptr::null());
+            }
+        }
+    }
+}
+
+/// An event watch added with `Sdl::add_event_watch`. The watch is removed
+/// when this is dropped.
+pub struct EventWatch<'a> {
+    callback: ~EventCallback<'a>,
+}
+
+impl<'a> EventWatch<'a> {
+    /// Adds a closure that is handed each event as it is added to the queue.
+    /// The value returned by the closure is ignored. This is usually called
+    /// through `Sdl::add_event_watch`.
+    pub fn new(_sdl: &Sdl, callback: |&Event|: 'a -> bool) -> EventWatch<'a> {
+        let watch = EventWatch { callback: EventCallback::new(callback) };
+        unsafe { SDL_AddEventWatch(event_callback, watch.callback.as_userdata()) };
+        watch
+    }
+}
+
+#[unsafe_destructor]
+impl<'a> Drop for EventWatch<'a> {
+    fn drop(&mut self) {
+        unsafe { SDL_DelEventWatch(event_callback, self.callback.as_userdata()) };
+    }
+}
+
+/// Runs the closure over every event in the queue, removing the events for
+/// which it returns `false`. This is usually called through
+/// `Sdl::filter_events`.
+pub fn filter_events(_sdl: &Sdl, callback: |&Event| -> bool) {
+    let callback = EventCallback::new(callback);
+    unsafe { SDL_FilterEvents(discarding_event_callback, callback.as_userdata()) };
+}
+
+/// An iterator that takes events off the queue until it is empty. This is
+/// created by `Sdl::poll_event_iter`.
+pub struct EventPollIterator<'a> {
+    pub sdl: &'a Sdl,
+}
+
+impl<'a> Iterator<EventStamped> for EventPollIterator<'a> {
+    fn next(&mut self) -> Option<EventStamped> {
+        self.sdl.poll_event()
+    }
+}
+
+// The address of this static is stored in the `data2` field of the custom
+// events created by this module, marking `data1` as a `~Rc<~Any:Send>` that
+// is owned by the event. The markers have distinct values, as statics with
+// equal values may be merged into one address.
+static CUSTOM_PAYLOAD_MARKER: u8 = 1;
+
+fn custom_payload_marker() -> *c_void {
+    &CUSTOM_PAYLOAD_MARKER as *u8 as *c_void
+}
+
+// Like `CUSTOM_PAYLOAD_MARKER`, but marking `data1` as a `~~Any:Send` pushed
+// by an `EventSender` from another task.
+static SENT_PAYLOAD_MARKER: u8 = 2;
+
+fn sent_payload_marker() -> *c_void {
+    &SENT_PAYLOAD_MARKER as *u8 as *c_void
+}
+
+/// The value carried by a `Custom` event. Clones of the payload share the
+/// same value, and payloads compare equal only if they share a value.
+pub struct CustomPayload {
+    value: Rc<~Any:Send>,
+}
+
+impl CustomPayload {
+    /// Returns a reference to the value if it is of type `T`.
+    pub fn downcast_ref<'a, T: 'static>(&'a self) -> Option<&'a T> {
+        let value: &'a Any = &**self.value.deref();
+        value.as_ref::<T>()
+    }
+}
+
+impl Clone for CustomPayload {
+    fn clone(&self) -> CustomPayload {
+        CustomPayload { value: self.value.clone() }
+    }
+}
+
+impl Eq for CustomPayload {
+    fn eq(&self, other: &CustomPayload) -> bool {
+        (self.value.deref() as *~Any:Send) == (other.value.deref() as *~Any:Send)
+    }
+}
+
+/// A custom event type whose events carry a value of type `T`. Values are
+/// boxed when the event is created and freed once the last `Event` referring
+/// to them is dropped, including when the event is taken off the queue and
+/// never read.
+///
+/// # Example
+///
+/// ~~~
+/// let loaded = sdl2.register_custom_event::<~str>().unwrap();
+/// sdl2.push_event(&loaded.event(~"level1.map")).unwrap();
+///
+/// for (_, event) in sdl2.poll_event_iter() {
+///     match loaded.get(&event) {
+///         Some(name) => println!("loaded {}", *name),
+///         None => {},
+///     }
+/// }
+/// ~~~
+pub struct CustomEventType<T> {
+    event_type: u32,
+}
+
+impl<T: Send> Clone for CustomEventType<T> {
+    fn clone(&self) -> CustomEventType<T> {
+        CustomEventType { event_type: self.event_type }
+    }
+}
+
+impl<T: Send> CustomEventType<T> {
+    /// Reserves a new user event type with SDL. This is usually called
+    /// through `Sdl::register_custom_event`.
+    pub fn register(_sdl: &Sdl) -> SdlResult<CustomEventType<T>> {
+        let event_type = unsafe { SDL_RegisterEvents(1) };
+        if event_type.bits() == -1 as u32 {
+            Err(~"No more user event types can be registered")
+        } else {
+            Ok(CustomEventType { event_type: event_type.bits() })
+        }
+    }
+
+    /// The event type reserved with SDL.
+    pub fn id(&self) -> u32 {
+        self.event_type
+    }
+
+    /// Creates an event of this type carrying the supplied value.
+    pub fn event(&self, value: T) -> Event {
+        Custom {
+            event_type: self.event_type,
+            payload:    CustomPayload { value: Rc::new(~value as ~Any:Send) },
+        }
+    }
+
+    /// Returns the value carried by the event if it is of this type.
+    pub fn get<'a>(&self, event: &'a Event) -> Option<&'a T> {
+        match *event {
+            Custom { event_type, ref payload } if event_type == self.event_type => {
+                payload.downcast_ref::<T>()
+            },
+            _ => None,
+        }
+    }
+}
+
+/// A handle for pushing custom events from other tasks, for example to wake
+/// a main loop that is blocked in `Sdl::wait_event`. This is created by
+/// `Sdl::event_sender`. Unlike `Sdl` it can be sent between tasks, as
+/// `SDL_PushEvent` is thread safe, but it can do nothing other than push
+/// custom events.
+///
+/// Events pushed by a sender are let through event filters without calling
+/// them, and are not handed to event watches, because SDL would call them on
+/// the pushing task. Until they are taken off the queue, such events are
+/// seen by `Sdl::peek_events` as `Unknown` events, because the value only
+/// becomes shareable once it reaches the receiving task.
+///
+/// # Example
+///
+/// ~~~
+/// let loaded = sdl2.register_custom_event::<~str>().unwrap();
+/// let sender = sdl2.event_sender();
+/// spawn(proc() {
+///     // ...
+///     sender.push_custom(&loaded, ~"level1.map").unwrap();
+/// });
+/// ~~~
+#[deriving(Clone)]
+pub struct EventSender {
+    _private: (),
+}
+
+impl EventSender {
+    /// Creates a sender. This is usually called through
+    /// `Sdl::event_sender`.
+    pub fn new(_sdl: &Sdl) -> EventSender {
+        EventSender { _private: () }
+    }
+
+    /// Adds an event carrying `value` to the back of the queue, returning
+    /// `false` if it was dropped by the event filter.
+    pub fn push_custom<T: Send>(&self, event_type: &CustomEventType<T>,
+                                value: T) -> SdlResult<bool> {
+        let value: ~~Any:Send = ~(~value as ~Any:Send);
+        let mut raw = SDL_Event::new(SDL_EventType::new_user_event(event_type.id()));
+        unsafe {
+            let data = raw.user_mut();
+            data.data1 = transmute(value);
+            data.data2 = sent_payload_marker();
+        }
+        push_sdl_event(raw)
+    }
 }
@@ -154,6 +154,18 @@ pub struct SDL_Event {
     padding: [Uint8, ..(56 * 8) - (32/8 + 32/8)],
 }
 
+impl SDL_Event {
+    /// Create an event of the supplied type with its timestamp and data
+    /// zeroed. This is also useful as a buffer for SDL to write events into.
+    pub fn new(event_type: SDL_EventType) -> SDL_Event {
+        SDL_Event {
+            event_type: event_type,
+            timestamp:  0,
+            padding:    [0, ..(56 * 8) - (32/8 + 32/8)],
+        }
+    }
+}
+
 impl SDL_Event {
     // These event getters check the `event_type` first, meaning they are slower
     // than the unsafe casts, however they should be safe if SDL behaves. :)
@@ -203,6 +215,30 @@ impl SDL_Event {
     pub unsafe fn mgesture<'a>(&'a self)    -> &'a SDL_MultiGestureEvent             { transmute(self) }
     pub unsafe fn dgesture<'a>(&'a self)    -> &'a SDL_DollarGestureEvent            { transmute(self) }
     pub unsafe fn drop_<'a>(&'a self)       -> &'a SDL_DropEvent                     { transmute(self) }
+
+    // Unsafe mutable event casts – make sure you set the `event_type` to match!
+    pub unsafe fn window_mut<'a>(&'a mut self)    -> &'a mut SDL_WindowEvent                   { transmute(self) }
+    pub unsafe fn key_mut<'a>(&'a mut self)       -> &'a mut SDL_KeyboardEvent                 { transmute(self) }
+    pub unsafe fn edit_mut<'a>(&'a mut self)      -> &'a mut SDL_TextEditingEvent              { transmute(self) }
+    pub unsafe fn text_mut<'a>(&'a mut self)      -> &'a mut SDL_TextInputEvent                { transmute(self) }
+    pub unsafe fn motion_mut<'a>(&'a mut self)    -> &'a mut SDL_MouseMotionEvent              { transmute(self) }
+    pub unsafe fn button_mut<'a>(&'a mut self)    -> &'a mut SDL_MouseButtonEvent              { transmute(self) }
+    pub unsafe fn wheel_mut<'a>(&'a mut self)     -> &'a mut SDL_MouseWheelEvent               { transmute(self) }
+    pub unsafe fn jaxis_mut<'a>(&'a mut self)     -> &'a mut SDL_JoyAxisEvent                  { transmute(self) }
+    pub unsafe fn jball_mut<'a>(&'a mut self)     -> &'a mut SDL_JoyBallEvent                  { transmute(self) }
+    pub unsafe fn jhat_mut<'a>(&'a mut self)      -> &'a mut SDL_JoyHatEvent                   { transmute(self) }
+    pub unsafe fn jbutton_mut<'a>(&'a mut self)   -> &'a mut SDL_JoyButtonEvent                { transmute(self) }
+    pub unsafe fn jdevice_mut<'a>(&'a mut self)   -> &'a mut SDL_JoyDeviceEvent                { transmute(self) }
+    pub unsafe fn caxis_mut<'a>(&'a mut self)     -> &'a mut SDL_ControllerAxisEvent           { transmute(self) }
+    pub unsafe fn cbutton_mut<'a>(&'a mut self)   -> &'a mut SDL_ControllerButtonEvent         { transmute(self) }
+    pub unsafe fn cdevice_mut<'a>(&'a mut self)   -> &'a mut SDL_ControllerDeviceEvent         { transmute(self) }
+    pub unsafe fn quit_mut<'a>(&'a mut self)      -> &'a mut SDL_QuitEvent                     { transmute(self) }
+    pub unsafe fn user_mut<'a>(&'a mut self)      -> &'a mut SDL_UserEvent                     { transmute(self) }
+    pub unsafe fn syswm_mut<'a>(&'a mut self)     -> &'a mut SDL_SysWMEvent                    { transmute(self) }
+    pub unsafe fn tfinger_mut<'a>(&'a mut self)   -> &'a mut SDL_TouchFingerEvent              { transmute(self) }
+    pub unsafe fn mgesture_mut<'a>(&'a mut self)  -> &'a mut SDL_MultiGestureEvent             { transmute(self) }
+    pub unsafe fn dgesture_mut<'a>(&'a mut self)  -> &'a mut SDL_DollarGestureEvent            { transmute(self) }
+    pub unsafe fn drop_mut<'a>(&'a mut self)      -> &'a mut SDL_DropEvent                     { transmute(self) }
 }
 
 // There is no need for this struct because the fields are already covered by
@@ -525,8 +561,8 @@ extern "C" {
     pub fn SDL_WaitEvent(event: *SDL_Event) -> c_int;
     pub fn SDL_WaitEventTimeout(event: *SDL_Event, timeout: c_int) -> c_int;
     pub fn SDL_PushEvent(event: *SDL_Event) -> c_int;
-    pub fn SDL_SetEventFilter(filter: SDL_EventFilter, userdata: *c_void);
-    pub fn SDL_GetEventFilter(filter: *SDL_EventFilter, userdata: **c_void) -> SDL_bool;
+    pub fn SDL_SetEventFilter(filter: Option<SDL_EventFilter>, userdata: *c_void);
+    pub fn SDL_GetEventFilter(filter: *mut Option<SDL_EventFilter>, userdata: *mut *c_void) -> SDL_bool;
     pub fn SDL_AddEventWatch(filter: SDL_EventFilter, userdata: *c_void);
     pub fn SDL_DelEventWatch(filter: SDL_EventFilter, userdata: *c_void);
     pub fn SDL_FilterEvents(filter: SDL_EventFilter, userdata: *c_void);
@@ -16,14 +16,16 @@ use ffi::keycode::{SDL_Keycode, SDL_Keymod};
 use ffi::rect::SDL_Rect;
 use ffi::scancode::SDL_Scancode;
 use ffi::video::SDL_Window;
-use ffi::stdinc::{SDL_bool, Uint8, Uint16, Uint32};
+use ffi::stdinc::{SDL_bool, Sint32, Uint8, Uint16, Uint32};
 use libc::{c_char, c_int};
 
 // SDL_keyboard.h
 
 pub struct SDL_Keysym {
     pub scancode:   SDL_Scancode,
-    pub sym:        SDL_Keycode,
+    // Not an `SDL_Keycode`, as keys that produce a character report its
+    // code point, which need not be a variant of the enum
+    pub sym:        Sint32,
     pub key_mod:    Uint16,
     unused:         Uint32,
 }
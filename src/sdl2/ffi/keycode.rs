@@ -23,6 +23,7 @@ macro_rules! SDL_SCANCODE_TO_KEYCODE(
 )
 
 #[repr(i32)]
+#[deriving(Clone, Eq, FromPrimitive)]
 pub enum SDL_Keycode {
     SDLK_UNKNOWN             = 0,
 
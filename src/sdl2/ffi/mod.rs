@@ -90,7 +90,7 @@ pub mod mouse;          // SDL_mouse.h
 // skipped              // SDL_opengl.h
 // skipped              // SDL_opengles.h
 // skipped              // SDL_opengles2.h
-// pub mod pixels;         // SDL_pixels.h
+pub mod pixels;         // SDL_pixels.h
 // skipped              // SDL_platform.h
 pub mod power;          // SDL_power.h
 pub mod quit;           // SDL_quit.h
@@ -116,9 +116,10 @@ pub mod syswm;          // SDL_syswm.h
 // skipped              // SDL_test_log.h
 // skipped              // SDL_test_md5.h
 // skipped              // SDL_test_random.h
-// skipped              // SDL_thread.h
+pub mod thread;         // SDL_thread.h
 pub mod timer;          // SDL_timer.h
 pub mod touch;          // SDL_touch.h
 // skipped              // SDL_types.h
 pub mod version;        // SDL_version.h
+#[macro_escape]
 pub mod video;          // SDL_video.h
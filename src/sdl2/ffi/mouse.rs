@@ -55,14 +55,18 @@ extern "C" {
     pub fn SDL_ShowCursor(toggle: c_int) -> c_int;
 }
 
-// #define SDL_BUTTON(X)       (1 << ((X)-1))
-// #define SDL_BUTTON_LEFT     1
-// #define SDL_BUTTON_MIDDLE   2
-// #define SDL_BUTTON_RIGHT    3
-// #define SDL_BUTTON_X1       4
-// #define SDL_BUTTON_X2       5
-// #define SDL_BUTTON_LMASK    SDL_BUTTON(SDL_BUTTON_LEFT)
-// #define SDL_BUTTON_MMASK    SDL_BUTTON(SDL_BUTTON_MIDDLE)
-// #define SDL_BUTTON_RMASK    SDL_BUTTON(SDL_BUTTON_RIGHT)
-// #define SDL_BUTTON_X1MASK   SDL_BUTTON(SDL_BUTTON_X1)
-// #define SDL_BUTTON_X2MASK   SDL_BUTTON(SDL_BUTTON_X2)
+#[inline]
+pub fn SDL_BUTTON(x: Uint8) -> Uint32 {
+    1 << ((x as Uint32) - 1)
+}
+
+pub static SDL_BUTTON_LEFT:     Uint8 = 1;
+pub static SDL_BUTTON_MIDDLE:   Uint8 = 2;
+pub static SDL_BUTTON_RIGHT:    Uint8 = 3;
+pub static SDL_BUTTON_X1:       Uint8 = 4;
+pub static SDL_BUTTON_X2:       Uint8 = 5;
+pub static SDL_BUTTON_LMASK:    Uint32 = 1 << 0;
+pub static SDL_BUTTON_MMASK:    Uint32 = 1 << 1;
+pub static SDL_BUTTON_RMASK:    Uint32 = 1 << 2;
+pub static SDL_BUTTON_X1MASK:   Uint32 = 1 << 3;
+pub static SDL_BUTTON_X2MASK:   Uint32 = 1 << 4;
@@ -160,7 +160,7 @@ macro_rules! SDL_ISPIXELFORMAT_ALPHA(
 )
 
 #[repr(u32)]
-#[deriving(Eq)]
+#[deriving(Clone, Eq, FromPrimitive)]
 // enum SDL_PixelFormat {
 pub enum SDL_PixelFormat_ {
     SDL_PIXELFORMAT_UNKNOWN,
@@ -241,18 +241,18 @@ pub struct SDL_PixelFormat {
 }
 
 extern "C" {
-    fn SDL_GetPixelFormatName(format: Uint32) -> *c_char;
-    fn SDL_PixelFormatEnumToMasks(format: Uint32, bpp: *c_int, Rmask: *mut Uint32, Gmask: *mut Uint32, Bmask: *mut Uint32, Amask: *mut Uint32) -> SDL_bool;
-    fn SDL_MasksToPixelFormatEnum(bpp: c_int, Rmask: Uint32, Gmask: Uint32, Bmask: Uint32, Amask: Uint32) -> Uint32;
-    fn SDL_AllocFormat(pixel_format: Uint32) -> *SDL_PixelFormat;
-    fn SDL_FreeFormat(format: *mut SDL_PixelFormat);
-    fn SDL_AllocPalette(ncolors: c_int) -> *SDL_Palette;
-    fn SDL_SetPixelFormatPalette(format: *mut SDL_PixelFormat, palette: *SDL_Palette) -> c_int;
-    fn SDL_SetPaletteColors(palette: *mut SDL_Palette, colors: *SDL_Color, firstcolor: c_int, ncolors: c_int) -> c_int;
-    fn SDL_FreePalette(palette: *mut SDL_Palette);
-    fn SDL_MapRGB(format: *SDL_PixelFormat, r: Uint8, g: Uint8, b: Uint8) -> Uint32;
-    fn SDL_MapRGBA(format: *SDL_PixelFormat, r: Uint8, g: Uint8, b: Uint8, a: Uint8) -> Uint32;
-    fn SDL_GetRGB(pixel: Uint32, format: *SDL_PixelFormat, r: *mut Uint8, g: *mut Uint8, b: *mut Uint8);
-    fn SDL_GetRGBA(pixel: Uint32, format: *SDL_PixelFormat, r: *mut Uint8, g: *mut Uint8, b: *mut Uint8, a: *mut Uint8);
-    fn SDL_CalculateGammaRamp(gamma: c_float, ramp: *mut Uint16);
+    pub fn SDL_GetPixelFormatName(format: Uint32) -> *c_char;
+    pub fn SDL_PixelFormatEnumToMasks(format: Uint32, bpp: *c_int, Rmask: *mut Uint32, Gmask: *mut Uint32, Bmask: *mut Uint32, Amask: *mut Uint32) -> SDL_bool;
+    pub fn SDL_MasksToPixelFormatEnum(bpp: c_int, Rmask: Uint32, Gmask: Uint32, Bmask: Uint32, Amask: Uint32) -> Uint32;
+    pub fn SDL_AllocFormat(pixel_format: Uint32) -> *SDL_PixelFormat;
+    pub fn SDL_FreeFormat(format: *mut SDL_PixelFormat);
+    pub fn SDL_AllocPalette(ncolors: c_int) -> *SDL_Palette;
+    pub fn SDL_SetPixelFormatPalette(format: *mut SDL_PixelFormat, palette: *SDL_Palette) -> c_int;
+    pub fn SDL_SetPaletteColors(palette: *mut SDL_Palette, colors: *SDL_Color, firstcolor: c_int, ncolors: c_int) -> c_int;
+    pub fn SDL_FreePalette(palette: *mut SDL_Palette);
+    pub fn SDL_MapRGB(format: *SDL_PixelFormat, r: Uint8, g: Uint8, b: Uint8) -> Uint32;
+    pub fn SDL_MapRGBA(format: *SDL_PixelFormat, r: Uint8, g: Uint8, b: Uint8, a: Uint8) -> Uint32;
+    pub fn SDL_GetRGB(pixel: Uint32, format: *SDL_PixelFormat, r: *mut Uint8, g: *mut Uint8, b: *mut Uint8);
+    pub fn SDL_GetRGBA(pixel: Uint32, format: *SDL_PixelFormat, r: *mut Uint8, g: *mut Uint8, b: *mut Uint8, a: *mut Uint8);
+    pub fn SDL_CalculateGammaRamp(gamma: c_float, ramp: *mut Uint16);
 }
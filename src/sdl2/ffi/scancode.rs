@@ -15,6 +15,7 @@
 // SDL_scancode.h
 
 #[repr(C)]
+#[deriving(Clone, Eq, FromPrimitive)]
 pub enum SDL_Scancode {
     SDL_SCANCODE_UNKNOWN = 0,
     SDL_SCANCODE_A = 4,
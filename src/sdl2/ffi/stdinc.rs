@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use libc::{c_void, size_t};
+
 // SDL_stdinc.h
 
 #[deriving(Eq)]
@@ -44,3 +46,8 @@ pub type Sint32 = i32;
 pub type Uint32 = u32;
 pub type Sint64 = i64;
 pub type Uint64 = u64;
+
+extern "C" {
+    pub fn SDL_malloc(size: size_t) -> *c_void;
+    pub fn SDL_free(mem: *c_void);
+}
@@ -23,8 +23,8 @@ use libc::{c_int, c_void};
 pub struct SDL_Surface {
     pub flags: Uint32,
     pub format: *c_void, // *SDL_PixelFormat,
-    pub h: c_int,
     pub w: c_int,
+    pub h: c_int,
     pub pitch: c_int,
     pub pixels: *c_void,
     pub userdata: *c_void,
@@ -0,0 +1,26 @@
+// Copyright 2014 The sdl2-rs Developers.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use libc::c_ulong;
+
+// SDL_thread.h
+
+// Only the thread identification functions are bound, as Rust has its own
+// threads and synchronisation primitives.
+
+pub type SDL_threadID = c_ulong;
+
+extern "C" {
+    pub fn SDL_ThreadID() -> SDL_threadID;
+}
@@ -0,0 +1,294 @@
+// Copyright 2014 The sdl2-rs Developers.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Frame based tracking of keyboard, mouse and game controller input.
+
+use std::slice;
+use libc::c_int;
+
+use event::*;
+use ffi;
+use ffi::joystick::SDL_JoystickID;
+use ffi::scancode::{SDL_Scancode, SDL_NUM_SCANCODES};
+use Sdl;
+
+/// The left mouse button.
+pub static LeftButton: u8 = ffi::mouse::SDL_BUTTON_LEFT;
+
+/// The middle mouse button.
+pub static MiddleButton: u8 = ffi::mouse::SDL_BUTTON_MIDDLE;
+
+/// The right mouse button.
+pub static RightButton: u8 = ffi::mouse::SDL_BUTTON_RIGHT;
+
+/// The first extra mouse button.
+pub static X1Button: u8 = ffi::mouse::SDL_BUTTON_X1;
+
+/// The second extra mouse button.
+pub static X2Button: u8 = ffi::mouse::SDL_BUTTON_X2;
+
+/// Returns the bit for the button in a mouse button state, or zero for
+/// buttons that the state cannot hold.
+fn button_mask(button: u8) -> u32 {
+    if button < 1 || button > 32 { 0 } else { ffi::mouse::SDL_BUTTON(button) }
+}
+
+/// Returns the state of the key in one of the per-scancode vectors. Scancodes
+/// that do not name a key, such as `SDL_NUM_SCANCODES`, are never set.
+fn key_state(keys: &Vec<bool>, scancode: SDL_Scancode) -> bool {
+    let scancode = scancode as uint;
+    scancode < keys.len() && *keys.get(scancode)
+}
+
+/// Tracks which keys and buttons are held, and which were pressed or released
+/// during the current frame. Call `begin_frame` at the start of each frame,
+/// then feed it the frame's events with `handle_event`, or synchronise it
+/// with SDL's own state with `sync`.
+///
+/// Keys are identified by scancode, so queries follow the physical layout
+/// of the keyboard. Key repeats are not reported as presses.
+///
+/// # Example
+///
+/// ~~~
+/// let mut input = sdl2::input::InputState::new();
+/// loop {
+///     input.begin_frame();
+///     for (_, event) in sdl2.poll_event_iter() {
+///         input.handle_event(&event);
+///     }
+///     if input.was_key_pressed(sdl2::ffi::scancode::SDL_SCANCODE_SPACE) {
+///         // jump
+///     }
+/// }
+/// ~~~
+pub struct InputState {
+    keys_held:          Vec<bool>,
+    keys_pressed:       Vec<bool>,
+    keys_released:      Vec<bool>,
+
+    mouse_held:         u32,
+    mouse_pressed:      u32,
+    mouse_released:     u32,
+    mouse_x:            i32,
+    mouse_y:            i32,
+    mouse_xrel:         i32,
+    mouse_yrel:         i32,
+    wheel_x:            i32,
+    wheel_y:            i32,
+
+    controller_held:     Vec<(SDL_JoystickID, u8)>,
+    controller_pressed:  Vec<(SDL_JoystickID, u8)>,
+    controller_released: Vec<(SDL_JoystickID, u8)>,
+}
+
+impl InputState {
+    /// Creates a tracker with nothing held.
+    pub fn new() -> InputState {
+        let num_keys = SDL_NUM_SCANCODES as uint;
+        InputState {
+            keys_held:          Vec::from_elem(num_keys, false),
+            keys_pressed:       Vec::from_elem(num_keys, false),
+            keys_released:      Vec::from_elem(num_keys, false),
+
+            mouse_held:         0,
+            mouse_pressed:      0,
+            mouse_released:     0,
+            mouse_x:            0,
+            mouse_y:            0,
+            mouse_xrel:         0,
+            mouse_yrel:         0,
+            wheel_x:            0,
+            wheel_y:            0,
+
+            controller_held:     Vec::new(),
+            controller_pressed:  Vec::new(),
+            controller_released: Vec::new(),
+        }
+    }
+
+    /// Forgets the presses, releases and relative motion of the previous
+    /// frame. Held keys and buttons are kept.
+    pub fn begin_frame(&mut self) {
+        for key in self.keys_pressed.mut_iter() { *key = false }
+        for key in self.keys_released.mut_iter() { *key = false }
+        self.mouse_pressed = 0;
+        self.mouse_released = 0;
+        self.mouse_xrel = 0;
+        self.mouse_yrel = 0;
+        self.wheel_x = 0;
+        self.wheel_y = 0;
+        self.controller_pressed.clear();
+        self.controller_released.clear();
+    }
+
+    /// Updates the tracked state from an event.
+    pub fn handle_event(&mut self, event: &Event) {
+        match *event {
+            Keyboard { repeat: false, scancode, state, .. } => {
+                self.set_key(scancode as uint, state == Pressed);
+            },
+            MouseMotion { x, y, xrel, yrel, .. } => {
+                self.mouse_x = x;
+                self.mouse_y = y;
+                self.mouse_xrel += xrel;
+                self.mouse_yrel += yrel;
+            },
+            MouseButton { button, state, x, y, .. } => {
+                self.mouse_x = x;
+                self.mouse_y = y;
+                let held = self.mouse_held;
+                self.set_mouse_buttons(match state {
+                    Pressed  => held | button_mask(button),
+                    Released => held & !button_mask(button),
+                });
+            },
+            MouseWheel { x, y, .. } => {
+                self.wheel_x += x;
+                self.wheel_y += y;
+            },
+            ControllerButton { which, button, state } => {
+                self.set_controller_button(which, button, state == Pressed);
+            },
+            ControllerDevice { which, change: DeviceRemoved } => {
+                // Buttons held on a removed controller will never be released
+                let buttons: Vec<(SDL_JoystickID, u8)> =
+                    self.controller_held.iter().filter(|&&(id, _)| id == which).map(|&b| b).collect();
+                for &(id, button) in buttons.iter() {
+                    self.set_controller_button(id, button, false);
+                }
+            },
+            _ => {},
+        }
+    }
+
+    /// Synchronises the held keys, mouse buttons and mouse position with the
+    /// state that SDL has recorded, as of the last time events were pumped.
+    /// Changes since the last update are reported as presses and releases.
+    pub fn sync(&mut self, _sdl: &Sdl) {
+        let mut num_keys: c_int = 0;
+        unsafe {
+            let state = ffi::keyboard::SDL_GetKeyboardState(&mut num_keys);
+            slice::raw::buf_as_slice(state, num_keys as uint, |keys| {
+                for (scancode, &held) in keys.iter().enumerate() {
+                    self.set_key(scancode, held != 0);
+                }
+            });
+        }
+
+        let (mut x, mut y) = (0, 0);
+        let buttons = unsafe { ffi::mouse::SDL_GetMouseState(&mut x, &mut y) };
+        self.set_mouse_buttons(buttons);
+        self.mouse_x = x;
+        self.mouse_y = y;
+    }
+
+    fn set_key(&mut self, scancode: uint, held: bool) {
+        if scancode >= self.keys_held.len() { return }
+        let was_held = *self.keys_held.get(scancode);
+        if held && !was_held { *self.keys_pressed.get_mut(scancode) = true }
+        if !held && was_held { *self.keys_released.get_mut(scancode) = true }
+        *self.keys_held.get_mut(scancode) = held;
+    }
+
+    fn set_mouse_buttons(&mut self, held: u32) {
+        self.mouse_pressed |= held & !self.mouse_held;
+        self.mouse_released |= self.mouse_held & !held;
+        self.mouse_held = held;
+    }
+
+    fn set_controller_button(&mut self, which: SDL_JoystickID, button: u8, held: bool) {
+        let key = (which, button);
+        let was_held = self.controller_held.contains(&key);
+        if held && !was_held {
+            self.controller_held.push(key);
+            self.controller_pressed.push(key);
+        } else if !held && was_held {
+            self.controller_held.retain(|&k| k != key);
+            self.controller_released.push(key);
+        }
+    }
+}
+
+/// Keyboard queries
+impl InputState {
+    /// Returns `true` if the key is held down.
+    pub fn is_key_held(&self, scancode: SDL_Scancode) -> bool {
+        key_state(&self.keys_held, scancode)
+    }
+
+    /// Returns `true` if the key was pressed during this frame.
+    pub fn was_key_pressed(&self, scancode: SDL_Scancode) -> bool {
+        key_state(&self.keys_pressed, scancode)
+    }
+
+    /// Returns `true` if the key was released during this frame.
+    pub fn was_key_released(&self, scancode: SDL_Scancode) -> bool {
+        key_state(&self.keys_released, scancode)
+    }
+}
+
+/// Mouse queries
+impl InputState {
+    /// Returns `true` if the mouse button, such as `LeftButton`, is held down.
+    pub fn is_mouse_button_held(&self, button: u8) -> bool {
+        self.mouse_held & button_mask(button) != 0
+    }
+
+    /// Returns `true` if the mouse button was pressed during this frame.
+    pub fn was_mouse_button_pressed(&self, button: u8) -> bool {
+        self.mouse_pressed & button_mask(button) != 0
+    }
+
+    /// Returns `true` if the mouse button was released during this frame.
+    pub fn was_mouse_button_released(&self, button: u8) -> bool {
+        self.mouse_released & button_mask(button) != 0
+    }
+
+    /// Returns the last known position of the mouse, relative to the focused
+    /// window.
+    pub fn mouse_position(&self) -> (i32, i32) {
+        (self.mouse_x, self.mouse_y)
+    }
+
+    /// Returns the distance the mouse moved during this frame.
+    pub fn mouse_motion(&self) -> (i32, i32) {
+        (self.mouse_xrel, self.mouse_yrel)
+    }
+
+    /// Returns the distance the mouse wheel was scrolled during this frame.
+    pub fn wheel_delta(&self) -> (i32, i32) {
+        (self.wheel_x, self.wheel_y)
+    }
+}
+
+/// Game controller queries
+impl InputState {
+    /// Returns `true` if the button on the controller with the supplied
+    /// instance id is held down.
+    pub fn is_controller_button_held(&self, which: SDL_JoystickID, button: u8) -> bool {
+        self.controller_held.contains(&(which, button))
+    }
+
+    /// Returns `true` if the controller button was pressed during this frame.
+    pub fn was_controller_button_pressed(&self, which: SDL_JoystickID, button: u8) -> bool {
+        self.controller_pressed.contains(&(which, button))
+    }
+
+    /// Returns `true` if the controller button was released during this
+    /// frame.
+    pub fn was_controller_button_released(&self, which: SDL_JoystickID, button: u8) -> bool {
+        self.controller_released.contains(&(which, button))
+    }
+}
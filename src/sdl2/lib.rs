@@ -14,15 +14,21 @@
 
 #![feature(globs)]
 #![feature(macro_rules)]
+#![feature(struct_variant)]
 
 #![comment = "Bindings and wrapper for SDL2."]
 #![crate_id = "github.com/bjz/sdl2-rs#sdl2:0.1"]
 #![crate_type = "lib"]
 
+extern crate collections;
 extern crate libc;
 extern crate sync;
 
+use libc::c_int;
 use std::c_str::CString;
+use std::cmp;
+use std::i32;
+use std::iter::range_inclusive;
 use std::kinds::marker;
 use std::str;
 
@@ -30,12 +36,27 @@ use std::str;
 pub use InitFlags = ffi::SDL_InitFlags;
 
 /// Foreign function bindings and low level types and enumerations for SDL.
+#[macro_escape]
 pub mod ffi;
 
 pub mod cpuinfo;
+pub mod event;
+pub mod input;
 pub mod power;
+pub mod recording;
 pub mod rect;
+pub mod surface;
 pub mod version;
+pub mod video;
+
+/// The result of an operation that can fail, holding the message reported
+/// by SDL on failure.
+pub type SdlResult<T> = Result<T, ~str>;
+
+/// Returns the message describing the last error that occurred in SDL.
+pub fn get_error() -> ~str {
+    unsafe { str::raw::from_c_str(ffi::error::SDL_GetError()) }
+}
 
 /// Initilise the timer subsystem.
 pub static InitTimer: InitFlags = ffi::SDL_INIT_TIMER;
@@ -153,6 +174,234 @@ impl Sdl {
     pub fn pump_events(&self) {
         unsafe { ffi::events::SDL_PumpEvents() }
     }
+
+    /// Takes the next event off the queue, returning `None` if the queue is
+    /// empty.
+    pub fn poll_event(&self) -> Option<event::EventStamped> {
+        let mut raw = ffi::events::SDL_Event::new(ffi::events::SDL_FIRSTEVENT);
+        if unsafe { ffi::events::SDL_PollEvent(&mut raw) } == 1 {
+            Some(event::make_event(raw))
+        } else {
+            None
+        }
+    }
+
+    /// Returns an iterator that takes events off the queue until it is empty.
+    ///
+    /// # Example
+    ///
+    /// ~~~
+    /// for (timestamp, event) in sdl2.poll_event_iter() {
+    ///     match event {
+    ///         sdl2::event::Quit => { /* ... */ },
+    ///         _ => {},
+    ///     }
+    /// }
+    /// ~~~
+    pub fn poll_event_iter<'a>(&'a self) -> event::EventPollIterator<'a> {
+        event::EventPollIterator { sdl: self }
+    }
+
+    /// Adds an event to the back of the queue, returning `false` if it was
+    /// dropped by the event filter. SDL gives the event a new timestamp.
+    /// Fails without pushing anything if the event cannot be represented as
+    /// a raw SDL event, see `Event::to_sdl_event`.
+    pub fn push_event(&self, event: &event::Event) -> SdlResult<bool> {
+        event::push_sdl_event(try!(event.to_sdl_event()))
+    }
+
+    /// Returns a handle that other tasks can use to push custom events.
+    pub fn event_sender(&self) -> event::EventSender {
+        event::EventSender::new(self)
+    }
+
+    /// Reserves a new event type whose events carry a value of type `T`.
+    pub fn register_custom_event<T: Send>(&self) -> SdlResult<event::CustomEventType<T>> {
+        event::CustomEventType::register(self)
+    }
+
+    /// Adds an event carrying `value` to the back of the queue. See
+    /// `push_event` for details.
+    pub fn push_custom_event<T: Send>(&self, event_type: &event::CustomEventType<T>,
+                                      value: T) -> SdlResult<bool> {
+        self.push_event(&event_type.event(value))
+    }
+
+    /// Sets a closure that is called as each event is added to the queue,
+    /// dropping the events for which it returns `false`. This replaces any
+    /// previously set filter, and the filter is removed when the returned
+    /// value is dropped. Any events in the queue are discarded when the
+    /// filter is set and again when it is removed.
+    ///
+    /// The closure is called before the queue is updated, which is the only
+    /// way to react to events like `event::AppTerminating` in time on some
+    /// platforms. SDL calls filters on the thread that added the event, so
+    /// events added on any other thread than this one are let through
+    /// without calling the closure. This includes events from an
+    /// `event::EventSender`, and on some platforms, such as Android, the
+    /// application lifecycle events.
+    pub fn set_event_filter<'a>(&self, filter: |&event::Event|: 'a -> bool) -> event::EventFilter<'a> {
+        event::EventFilter::new(self, filter)
+    }
+
+    /// Adds a closure that is called as each event is added to the queue.
+    /// Unlike a filter the closure cannot drop events, and any number of
+    /// watches can be added. The watch is removed when the returned value is
+    /// dropped. Like a filter, the closure is not called for events added on
+    /// other threads.
+    pub fn add_event_watch<'a>(&self, watch: |&event::Event|: 'a -> bool) -> event::EventWatch<'a> {
+        event::EventWatch::new(self, watch)
+    }
+
+    /// Removes the events from the queue for which the closure returns
+    /// `false`.
+    pub fn filter_events(&self, filter: |&event::Event| -> bool) {
+        event::filter_events(self, filter)
+    }
+
+    /// Returns up to `max_events` events with types in the range `min` to
+    /// `max` inclusive from the front of the queue, leaving them in place.
+    pub fn peek_events(&self, min: event::EventType, max: event::EventType,
+                       max_events: uint) -> SdlResult<Vec<event::EventStamped>> {
+        self.peep_events(ffi::events::SDL_PEEKEVENT, min, max, max_events).map(|raws| {
+            raws.iter().map(|raw| (raw.timestamp, event::Event::from_sdl_event(raw))).collect()
+        })
+    }
+
+    /// Takes up to `max_events` events with types in the range `min` to `max`
+    /// inclusive off the front of the queue.
+    pub fn get_events(&self, min: event::EventType, max: event::EventType,
+                      max_events: uint) -> SdlResult<Vec<event::EventStamped>> {
+        self.peep_events(ffi::events::SDL_GETEVENT, min, max, max_events).map(|raws| {
+            raws.move_iter().map(event::make_event).collect()
+        })
+    }
+
+    fn peep_events(&self, action: ffi::events::SDL_eventaction,
+                   min: event::EventType, max: event::EventType,
+                   max_events: uint) -> SdlResult<Vec<ffi::events::SDL_Event>> {
+        let mut raws = Vec::from_fn(max_events, |_| {
+            ffi::events::SDL_Event::new(ffi::events::SDL_FIRSTEVENT)
+        });
+        let count = unsafe {
+            // SDL writes the events it takes or peeks into the buffer
+            let buffer = raws.as_mut_slice().as_mut_ptr() as *ffi::events::SDL_Event;
+            ffi::events::SDL_PeepEvents(buffer, max_events as c_int, action, min, max)
+        };
+        if count < 0 {
+            Err(get_error())
+        } else {
+            raws.truncate(count as uint);
+            Ok(raws)
+        }
+    }
+
+    /// Returns `true` if there is an event of the supplied type in the queue.
+    pub fn has_event(&self, event_type: event::EventType) -> bool {
+        unsafe { ffi::events::SDL_HasEvent(event_type).to_bool() }
+    }
+
+    /// Returns `true` if there is an event with a type in the range `min` to
+    /// `max` inclusive in the queue.
+    pub fn has_events(&self, min: event::EventType, max: event::EventType) -> bool {
+        unsafe { ffi::events::SDL_HasEvents(min, max).to_bool() }
+    }
+
+    /// Returns `true` if there is an event in the supplied category in the
+    /// queue.
+    pub fn has_category(&self, category: event::EventCategory) -> bool {
+        let (min, max) = category.range();
+        self.has_events(min, max)
+    }
+
+    /// Removes all the events of the supplied type from the queue.
+    pub fn flush_event(&self, event_type: event::EventType) {
+        self.flush_events(event_type, event_type)
+    }
+
+    /// Removes all the events with a type in the range `min` to `max`
+    /// inclusive from the queue.
+    pub fn flush_events(&self, min: event::EventType, max: event::EventType) {
+        // The events are taken off the queue rather than being discarded with
+        // `SDL_FlushEvents`, so that the memory they own is released.
+        static BATCH_SIZE: uint = 32;
+        loop {
+            match self.peep_events(ffi::events::SDL_GETEVENT, min, max, BATCH_SIZE) {
+                Ok(raws) => {
+                    for raw in raws.iter() {
+                        unsafe { event::free_sdl_event(raw) };
+                    }
+                    if raws.len() < BATCH_SIZE { break }
+                },
+                Err(_) => break,
+            }
+        }
+    }
+
+    /// Removes all the events in the supplied category from the queue.
+    pub fn flush_category(&self, category: event::EventCategory) {
+        let (min, max) = category.range();
+        self.flush_events(min, max)
+    }
+
+    /// Sets whether events of the supplied type are added to the queue,
+    /// returning whether they were enabled beforehand. Disabling the types
+    /// that an application does not use, such as `SDL_MOUSEMOTION` or
+    /// `SDL_JOYAXISMOTION`, saves SDL from generating them at all. Events of
+    /// the type that are already queued are removed when it is disabled.
+    pub fn set_event_enabled(&self, event_type: event::EventType, enabled: bool) -> bool {
+        // SDL would discard the queued events without releasing their memory
+        if !enabled { self.flush_event(event_type) }
+        let state = if enabled { ffi::events::SDL_ENABLE } else { ffi::events::SDL_DISABLE };
+        unsafe { ffi::events::SDL_EventState(event_type, state) == ffi::events::SDL_ENABLE as u8 }
+    }
+
+    /// Returns `true` if events of the supplied type are added to the queue.
+    pub fn is_event_enabled(&self, event_type: event::EventType) -> bool {
+        unsafe { ffi::events::SDL_GetEventState(event_type) == ffi::events::SDL_ENABLE as u8 }
+    }
+
+    /// Sets whether events of every type in the supplied category are added
+    /// to the queue, like `set_event_enabled`.
+    pub fn set_category_enabled(&self, category: event::EventCategory, enabled: bool) {
+        let (min, max) = category.range();
+        if !enabled { self.flush_events(min, max) }
+        let state = if enabled { ffi::events::SDL_ENABLE } else { ffi::events::SDL_DISABLE };
+        for bits in range_inclusive(min.bits(), max.bits()) {
+            let event_type = ffi::events::SDL_EventType::from_bits_truncate(bits);
+            unsafe { ffi::events::SDL_EventState(event_type, state) };
+        }
+    }
+
+    /// Blocks until an event is available, then takes it off the queue.
+    pub fn wait_event(&self) -> SdlResult<event::EventStamped> {
+        let mut raw = ffi::events::SDL_Event::new(ffi::events::SDL_FIRSTEVENT);
+        if unsafe { ffi::events::SDL_WaitEvent(&mut raw) } == 1 {
+            Ok(event::make_event(raw))
+        } else {
+            Err(get_error())
+        }
+    }
+
+    /// Blocks until an event is available or until `timeout` milliseconds
+    /// have elapsed, returning `None` in the latter case.
+    pub fn wait_event_timeout(&self, timeout: uint) -> SdlResult<Option<event::EventStamped>> {
+        // SDL reports both timeouts and errors by returning `0`, and it only
+        // fails when the event queue is not running, so that is checked
+        // first. The error message cannot be used to tell them apart, as the
+        // backends may set one while pumping events without failing.
+        if !self.was_init(InitEvents).contains(InitEvents) {
+            return Err(~"the event subsystem is not initialised");
+        }
+        // A negative timeout would make SDL wait forever
+        let timeout = cmp::min(timeout, i32::MAX as uint) as c_int;
+        let mut raw = ffi::events::SDL_Event::new(ffi::events::SDL_FIRSTEVENT);
+        if unsafe { ffi::events::SDL_WaitEventTimeout(&mut raw, timeout) } == 1 {
+            Ok(Some(event::make_event(raw)))
+        } else {
+            Ok(None)
+        }
+    }
 }
 
 /// Quit handling
@@ -162,6 +411,22 @@ impl Sdl {
     }
 }
 
+/// Video
+impl Sdl {
+    /// Returns a handle used to query the video drivers, displays and
+    /// display modes.
+    pub fn video(&self) -> video::VideoSubsystem {
+        video::VideoSubsystem::new(self)
+    }
+
+    /// Initialises video with the named driver, or the default driver if
+    /// `None` is given. See `VideoSubsystem::init_driver`.
+    pub fn init_video(&self, driver: Option<&str>) -> SdlResult<video::VideoSubsystem> {
+        let video = self.video();
+        video.init_driver(driver).map(|_| video)
+    }
+}
+
 /// Clipboard handling
 impl Sdl {
     /// Store the string in the clipboard, returning `false` if an error occured.
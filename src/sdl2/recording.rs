@@ -0,0 +1,497 @@
+// Copyright 2014 The sdl2-rs Developers.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Recording of polled events to a compact binary format, and replaying
+//! them back into the event queue.
+//!
+//! A recording starts with the bytes `SDL2EVTS` followed by the format
+//! version as a little endian `u16`. Each event is then stored as its
+//! timestamp and SDL event type, followed by the fields of the event. All
+//! numbers are little endian, and strings are stored as a `u32` byte length
+//! followed by UTF-8 data. Recordings with text longer than an SDL event can
+//! hold, or with filenames longer than 4096 bytes, are rejected when read.
+//!
+//! `SysWm` events refer to memory that only exists in the recording process,
+//! so they are skipped. The `data1` and `data2` pointers of `User` events are
+//! recorded as null for the same reason, and `Custom` events are recorded as
+//! `User` events without their payload.
+//!
+//! # Example
+//!
+//! ~~~
+//! let file = File::create(&Path::new("session.evts"));
+//! let mut recorder = sdl2::recording::Recorder::new(file).unwrap();
+//! for event in sdl2.poll_event_iter() {
+//!     recorder.record(&event).unwrap();
+//!     // ...
+//! }
+//! ~~~
+
+use std::io::{EndOfFile, InvalidInput, IoError, IoResult};
+use std::num::{FromPrimitive, Saturating};
+use std::ptr;
+use std::str;
+
+use event::*;
+use ffi;
+use ffi::events::SDL_EventType;
+use ffi::joystick::SDL_Hat;
+use ffi::keycode::SDL_Keymod;
+use {Sdl, SdlResult};
+
+/// The bytes that every recording starts with.
+pub static MAGIC: &'static [u8] = bytes!("SDL2EVTS");
+
+/// The version of the format written by `Recorder`. Recordings with a
+/// different version are rejected by `read_recording`.
+pub static FORMAT_VERSION: u16 = 1;
+
+/// Writes polled events to a recording.
+pub struct Recorder<W> {
+    writer: W,
+}
+
+impl<W: Writer> Recorder<W> {
+    /// Starts a recording by writing the header.
+    pub fn new(mut writer: W) -> IoResult<Recorder<W>> {
+        try!(writer.write(MAGIC));
+        try!(writer.write_le_u16(FORMAT_VERSION));
+        Ok(Recorder { writer: writer })
+    }
+
+    /// Appends an event to the recording. `SysWm` events are skipped, and
+    /// `Custom` events are recorded as `User` events with the same type and
+    /// a zero code, as their payload cannot be recorded.
+    pub fn record(&mut self, &(timestamp, ref event): &EventStamped) -> IoResult<()> {
+        match *event {
+            SysWm { .. } => return Ok(()),
+            _ => {},
+        }
+        let w = &mut self.writer;
+        try!(w.write_le_u32(timestamp));
+        try!(w.write_le_u32(event.event_type().bits()));
+        match *event {
+            Quit | AppTerminating | AppLowMemory | AppWillEnterBackground |
+            AppDidEnterBackground | AppWillEnterForeground | AppDidEnterForeground |
+            ClipboardUpdate | Unknown { .. } => {},
+
+            WindowEvent { window_id, kind } => {
+                let (event_id, data1, data2) = kind.to_sdl();
+                try!(w.write_le_u32(window_id));
+                try!(w.write_u8(event_id));
+                try!(w.write_le_i32(data1));
+                try!(w.write_le_i32(data2));
+            },
+            Keyboard { window_id, repeat, scancode, keycode, keymod, .. } => {
+                try!(w.write_le_u32(window_id));
+                try!(w.write_u8(repeat as u8));
+                try!(w.write_le_i32(scancode as i32));
+                try!(w.write_le_i32(keycode));
+                try!(w.write_le_u16(keymod.bits()));
+            },
+            TextEditing { window_id, ref text, start, length } => {
+                try!(w.write_le_u32(window_id));
+                try!(write_str(w, text.as_slice()));
+                try!(w.write_le_i32(start));
+                try!(w.write_le_i32(length));
+            },
+            TextInput { window_id, ref text } => {
+                try!(w.write_le_u32(window_id));
+                try!(write_str(w, text.as_slice()));
+            },
+            MouseMotion { window_id, which, state, x, y, xrel, yrel } => {
+                try!(w.write_le_u32(window_id));
+                try!(w.write_le_u32(which));
+                try!(w.write_le_u32(state));
+                try!(w.write_le_i32(x));
+                try!(w.write_le_i32(y));
+                try!(w.write_le_i32(xrel));
+                try!(w.write_le_i32(yrel));
+            },
+            MouseButton { window_id, which, button, x, y, .. } => {
+                try!(w.write_le_u32(window_id));
+                try!(w.write_le_u32(which));
+                try!(w.write_u8(button));
+                try!(w.write_le_i32(x));
+                try!(w.write_le_i32(y));
+            },
+            MouseWheel { window_id, which, x, y } => {
+                try!(w.write_le_u32(window_id));
+                try!(w.write_le_u32(which));
+                try!(w.write_le_i32(x));
+                try!(w.write_le_i32(y));
+            },
+            JoyAxis { which, axis, value } | ControllerAxis { which, axis, value } => {
+                try!(w.write_le_i32(which));
+                try!(w.write_u8(axis));
+                try!(w.write_le_i16(value));
+            },
+            JoyBall { which, ball, xrel, yrel } => {
+                try!(w.write_le_i32(which));
+                try!(w.write_u8(ball));
+                try!(w.write_le_i16(xrel));
+                try!(w.write_le_i16(yrel));
+            },
+            JoyHat { which, hat, state } => {
+                try!(w.write_le_i32(which));
+                try!(w.write_u8(hat));
+                try!(w.write_u8(state.bits()));
+            },
+            JoyButton { which, button, .. } | ControllerButton { which, button, .. } => {
+                try!(w.write_le_i32(which));
+                try!(w.write_u8(button));
+            },
+            JoyDevice { which, .. } | ControllerDevice { which, .. } => {
+                try!(w.write_le_i32(which));
+            },
+            TouchFinger { touch_id, finger_id, x, y, dx, dy, pressure, .. } => {
+                try!(w.write_le_i64(touch_id));
+                try!(w.write_le_i64(finger_id));
+                try!(w.write_le_f32(x));
+                try!(w.write_le_f32(y));
+                try!(w.write_le_f32(dx));
+                try!(w.write_le_f32(dy));
+                try!(w.write_le_f32(pressure));
+            },
+            MultiGesture { touch_id, d_theta, d_dist, x, y, num_fingers } => {
+                try!(w.write_le_i64(touch_id));
+                try!(w.write_le_f32(d_theta));
+                try!(w.write_le_f32(d_dist));
+                try!(w.write_le_f32(x));
+                try!(w.write_le_f32(y));
+                try!(w.write_le_u16(num_fingers));
+            },
+            DollarGesture { touch_id, gesture_id, num_fingers, error, x, y } => {
+                try!(w.write_le_i64(touch_id));
+                try!(w.write_le_i64(gesture_id));
+                try!(w.write_le_u32(num_fingers));
+                try!(w.write_le_f32(error));
+                try!(w.write_le_f32(x));
+                try!(w.write_le_f32(y));
+            },
+            DollarRecord { touch_id, gesture_id } => {
+                try!(w.write_le_i64(touch_id));
+                try!(w.write_le_i64(gesture_id));
+            },
+            DropFile { ref filename } => {
+                try!(write_str(w, filename.as_slice()));
+            },
+            User { window_id, code, .. } => {
+                try!(w.write_le_u32(window_id));
+                try!(w.write_le_i32(code));
+            },
+            Custom { .. } => {
+                try!(w.write_le_u32(0));
+                try!(w.write_le_i32(0));
+            },
+            SysWm { .. } => unreachable!(),
+        }
+        Ok(())
+    }
+
+    /// Finishes the recording, returning the underlying writer.
+    pub fn unwrap(self) -> W {
+        self.writer
+    }
+}
+
+fn write_str<W: Writer>(w: &mut W, s: &str) -> IoResult<()> {
+    try!(w.write_le_u32(s.len() as u32));
+    w.write_str(s)
+}
+
+// The longest filename accepted from a recording, so that a corrupt length
+// cannot cause a huge allocation
+static MAX_FILENAME_LEN: uint = 4096;
+
+/// Reads a string of at most `max_len` bytes.
+fn read_str<R: Reader>(r: &mut R, max_len: uint) -> IoResult<~str> {
+    let len = try!(r.read_le_u32());
+    if len as uint > max_len {
+        return Err(invalid_input("The recording contains a string that is too long"));
+    }
+    let bytes = try!(r.read_bytes(len as uint));
+    match str::from_utf8_owned(bytes) {
+        Some(s) => Ok(s),
+        None => Err(invalid_input("The recording contains a string that is not valid UTF-8")),
+    }
+}
+
+fn invalid_input(desc: &'static str) -> IoError {
+    IoError { kind: InvalidInput, desc: desc, detail: None }
+}
+
+/// Reads every event from a recording, checking the header first.
+pub fn read_recording<R: Reader>(reader: &mut R) -> IoResult<Vec<EventStamped>> {
+    let magic = try!(reader.read_bytes(MAGIC.len()));
+    if magic.as_slice() != MAGIC {
+        return Err(invalid_input("The data is not an event recording"));
+    }
+    if try!(reader.read_le_u16()) != FORMAT_VERSION {
+        return Err(invalid_input("The event recording has an unsupported format version"));
+    }
+
+    let mut events = Vec::new();
+    loop {
+        let timestamp = match reader.read_le_u32() {
+            Ok(timestamp) => timestamp,
+            Err(ref err) if err.kind == EndOfFile => return Ok(events),
+            Err(err) => return Err(err),
+        };
+        let event = try!(read_event(reader));
+        events.push((timestamp, event));
+    }
+}
+
+fn read_event<R: Reader>(r: &mut R) -> IoResult<Event> {
+    let event_type = SDL_EventType::from_bits_truncate(try!(r.read_le_u32()));
+    let t = event_type;
+    let pressed = |down: SDL_EventType| if t == down { Pressed } else { Released };
+    let event = match t {
+        t if t == ffi::events::SDL_QUIT                     => Quit,
+        t if t == ffi::events::SDL_APP_TERMINATING          => AppTerminating,
+        t if t == ffi::events::SDL_APP_LOWMEMORY            => AppLowMemory,
+        t if t == ffi::events::SDL_APP_WILLENTERBACKGROUND  => AppWillEnterBackground,
+        t if t == ffi::events::SDL_APP_DIDENTERBACKGROUND   => AppDidEnterBackground,
+        t if t == ffi::events::SDL_APP_WILLENTERFOREGROUND  => AppWillEnterForeground,
+        t if t == ffi::events::SDL_APP_DIDENTERFOREGROUND   => AppDidEnterForeground,
+        t if t == ffi::events::SDL_CLIPBOARDUPDATE          => ClipboardUpdate,
+        t if t.is_window() => WindowEvent {
+            window_id:  try!(r.read_le_u32()),
+            kind:       {
+                let event_id = try!(r.read_u8());
+                let data1 = try!(r.read_le_i32());
+                let data2 = try!(r.read_le_i32());
+                WindowEventKind::from_sdl(event_id, data1, data2)
+            },
+        },
+        t if t.is_key() => Keyboard {
+            window_id:  try!(r.read_le_u32()),
+            state:      pressed(ffi::events::SDL_KEYDOWN),
+            repeat:     try!(r.read_u8()) != 0,
+            scancode:   match FromPrimitive::from_i32(try!(r.read_le_i32())) {
+                Some(scancode) => scancode,
+                None => return Err(invalid_input("The recording contains an unknown scancode")),
+            },
+            keycode:    try!(r.read_le_i32()),
+            keymod:     SDL_Keymod::from_bits_truncate(try!(r.read_le_u16())),
+        },
+        t if t.is_edit() => TextEditing {
+            window_id:  try!(r.read_le_u32()),
+            text:       try!(read_str(r, ffi::events::SDL_TEXTEDITINGEVENT_TEXT_SIZE - 1)),
+            start:      try!(r.read_le_i32()),
+            length:     try!(r.read_le_i32()),
+        },
+        t if t.is_text() => TextInput {
+            window_id:  try!(r.read_le_u32()),
+            text:       try!(read_str(r, ffi::events::SDL_TEXTINPUTEVENT_TEXT_SIZE - 1)),
+        },
+        t if t.is_motion() => MouseMotion {
+            window_id:  try!(r.read_le_u32()),
+            which:      try!(r.read_le_u32()),
+            state:      try!(r.read_le_u32()),
+            x:          try!(r.read_le_i32()),
+            y:          try!(r.read_le_i32()),
+            xrel:       try!(r.read_le_i32()),
+            yrel:       try!(r.read_le_i32()),
+        },
+        t if t.is_button() => MouseButton {
+            window_id:  try!(r.read_le_u32()),
+            which:      try!(r.read_le_u32()),
+            button:     try!(r.read_u8()),
+            state:      pressed(ffi::events::SDL_MOUSEBUTTONDOWN),
+            x:          try!(r.read_le_i32()),
+            y:          try!(r.read_le_i32()),
+        },
+        t if t.is_wheel() => MouseWheel {
+            window_id:  try!(r.read_le_u32()),
+            which:      try!(r.read_le_u32()),
+            x:          try!(r.read_le_i32()),
+            y:          try!(r.read_le_i32()),
+        },
+        t if t.is_jaxis() => JoyAxis {
+            which:      try!(r.read_le_i32()),
+            axis:       try!(r.read_u8()),
+            value:      try!(r.read_le_i16()),
+        },
+        t if t.is_jball() => JoyBall {
+            which:      try!(r.read_le_i32()),
+            ball:       try!(r.read_u8()),
+            xrel:       try!(r.read_le_i16()),
+            yrel:       try!(r.read_le_i16()),
+        },
+        t if t.is_jhat() => JoyHat {
+            which:      try!(r.read_le_i32()),
+            hat:        try!(r.read_u8()),
+            state:      SDL_Hat::from_bits_truncate(try!(r.read_u8())),
+        },
+        t if t.is_jbutton() => JoyButton {
+            which:      try!(r.read_le_i32()),
+            button:     try!(r.read_u8()),
+            state:      pressed(ffi::events::SDL_JOYBUTTONDOWN),
+        },
+        t if t.is_jdevice() => JoyDevice {
+            which:      try!(r.read_le_i32()),
+            change:     if t == ffi::events::SDL_JOYDEVICEADDED { JoyAdded } else { JoyRemoved },
+        },
+        t if t.is_caxis() => ControllerAxis {
+            which:      try!(r.read_le_i32()),
+            axis:       try!(r.read_u8()),
+            value:      try!(r.read_le_i16()),
+        },
+        t if t.is_cbutton() => ControllerButton {
+            which:      try!(r.read_le_i32()),
+            button:     try!(r.read_u8()),
+            state:      pressed(ffi::events::SDL_CONTROLLERBUTTONDOWN),
+        },
+        t if t.is_cdevice() => ControllerDevice {
+            which:      try!(r.read_le_i32()),
+            change:     match t {
+                t if t == ffi::events::SDL_CONTROLLERDEVICEADDED    => DeviceAdded,
+                t if t == ffi::events::SDL_CONTROLLERDEVICEREMOVED  => DeviceRemoved,
+                _                                                   => DeviceRemapped,
+            },
+        },
+        t if t.is_tfinger() => TouchFinger {
+            touch_id:   try!(r.read_le_i64()),
+            finger_id:  try!(r.read_le_i64()),
+            change:     match t {
+                t if t == ffi::events::SDL_FINGERDOWN   => FingerDown,
+                t if t == ffi::events::SDL_FINGERUP     => FingerUp,
+                _                                       => FingerMotion,
+            },
+            x:          try!(r.read_le_f32()),
+            y:          try!(r.read_le_f32()),
+            dx:         try!(r.read_le_f32()),
+            dy:         try!(r.read_le_f32()),
+            pressure:   try!(r.read_le_f32()),
+        },
+        t if t.is_mgesture() => MultiGesture {
+            touch_id:   try!(r.read_le_i64()),
+            d_theta:    try!(r.read_le_f32()),
+            d_dist:     try!(r.read_le_f32()),
+            x:          try!(r.read_le_f32()),
+            y:          try!(r.read_le_f32()),
+            num_fingers: try!(r.read_le_u16()),
+        },
+        t if t.is_dgesture() => DollarGesture {
+            touch_id:   try!(r.read_le_i64()),
+            gesture_id: try!(r.read_le_i64()),
+            num_fingers: try!(r.read_le_u32()),
+            error:      try!(r.read_le_f32()),
+            x:          try!(r.read_le_f32()),
+            y:          try!(r.read_le_f32()),
+        },
+        t if t == ffi::events::SDL_DOLLARRECORD => DollarRecord {
+            touch_id:   try!(r.read_le_i64()),
+            gesture_id: try!(r.read_le_i64()),
+        },
+        t if t.is_drop_() => DropFile {
+            filename:   try!(read_str(r, MAX_FILENAME_LEN)),
+        },
+        t if t.is_user() => User {
+            event_type: t.bits(),
+            window_id:  try!(r.read_le_u32()),
+            code:       try!(r.read_le_i32()),
+            data1:      ptr::null(),
+            data2:      ptr::null(),
+        },
+        t => Unknown { event_type: t.bits() },
+    };
+    Ok(event)
+}
+
+/// How the events of a `Replay` are spaced out.
+#[deriving(Clone, Eq)]
+pub enum ReplayTiming {
+    /// Each event is pushed once the time between it and the first event of
+    /// the recording has elapsed since the replay started.
+    RealTime,
+    /// Every event is pushed the first time the replay is pumped.
+    AsFastAsPossible,
+}
+
+/// Pushes recorded events back into the event queue.
+///
+/// # Example
+///
+/// ~~~
+/// let events = sdl2::recording::read_recording(&mut file).unwrap();
+/// let mut replay = sdl2::recording::Replay::new(events, sdl2::recording::RealTime);
+/// while !replay.is_finished() {
+///     replay.pump(&sdl2).unwrap();
+///     for event in sdl2.poll_event_iter() {
+///         // ...
+///     }
+/// }
+/// ~~~
+pub struct Replay {
+    events: Vec<EventStamped>,
+    timing: ReplayTiming,
+    next: uint,
+    start: Option<u32>,
+}
+
+impl Replay {
+    pub fn new(events: Vec<EventStamped>, timing: ReplayTiming) -> Replay {
+        Replay { events: events, timing: timing, next: 0, start: None }
+    }
+
+    /// Returns `true` once every event has been pushed.
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.events.len()
+    }
+
+    /// Pushes the events that are due, returning the number of events
+    /// pushed. The first call starts the replay clock.
+    pub fn pump(&mut self, sdl: &Sdl) -> SdlResult<uint> {
+        let now = unsafe { ffi::timer::SDL_GetTicks() };
+        let start = match self.start {
+            Some(start) => start,
+            None => { self.start = Some(now); now },
+        };
+        let first = match self.events.as_slice().head() {
+            Some(&(timestamp, _)) => timestamp,
+            None => return Ok(0),
+        };
+
+        let mut pushed = 0;
+        while self.next < self.events.len() {
+            let (timestamp, ref event) = *self.events.get(self.next);
+            // The timestamps of a recording read from a file may be out of
+            // order, so the differences must not wrap around
+            if self.timing == RealTime
+            && timestamp.saturating_sub(first) > now.saturating_sub(start) {
+                break;
+            }
+            try!(sdl.push_event(event));
+            self.next += 1;
+            pushed += 1;
+        }
+        Ok(pushed)
+    }
+
+    /// Pushes every remaining event, sleeping between them as required by
+    /// the timing. This blocks until the replay is finished, so the queue
+    /// must be large enough to hold the events or be drained by a watch.
+    pub fn run(&mut self, sdl: &Sdl) -> SdlResult<()> {
+        while !self.is_finished() {
+            try!(self.pump(sdl));
+            if !self.is_finished() {
+                unsafe { ffi::timer::SDL_Delay(1) };
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,144 @@
+// Copyright 2014 The sdl2-rs Developers.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Safe wrappers for surfaces, which hold pixels in system memory.
+
+use std::cast::transmute;
+use std::i32;
+use std::slice;
+use libc::{c_int, c_void};
+
+use ffi;
+use ffi::surface::SDL_Surface;
+use {SdlResult, get_error};
+
+// The masks that give 32 bit pixels the byte order R, G, B, A in memory.
+#[cfg(target_endian = "little")]
+static RGBA_MASKS: (u32, u32, u32, u32) = (0x000000FF, 0x0000FF00, 0x00FF0000, 0xFF000000);
+#[cfg(target_endian = "big")]
+static RGBA_MASKS: (u32, u32, u32, u32) = (0xFF000000, 0x00FF0000, 0x0000FF00, 0x000000FF);
+
+/// A surface that is freed when dropped.
+pub struct Surface {
+    raw: *SDL_Surface,
+}
+
+impl Surface {
+    /// Creates a surface of 32 bit pixels, stored as the bytes red, green,
+    /// blue and alpha. The pixels are initially transparent black.
+    pub fn new_rgba(width: uint, height: uint) -> SdlResult<Surface> {
+        let (rmask, gmask, bmask, amask) = RGBA_MASKS;
+        let raw = unsafe {
+            ffi::surface::SDL_CreateRGBSurface(0, width as c_int, height as c_int, 32,
+                                               rmask, gmask, bmask, amask)
+        };
+        if raw.is_null() { Err(get_error()) } else { Ok(Surface { raw: raw }) }
+    }
+
+    /// Takes ownership of a surface created by SDL.
+    pub unsafe fn from_raw(raw: *SDL_Surface) -> Surface {
+        Surface { raw: raw }
+    }
+
+    /// Returns the underlying SDL surface, for use with the `ffi` functions.
+    pub fn raw(&self) -> *SDL_Surface {
+        self.raw
+    }
+
+    pub fn width(&self) -> uint {
+        unsafe { (*self.raw).w as uint }
+    }
+
+    pub fn height(&self) -> uint {
+        unsafe { (*self.raw).h as uint }
+    }
+
+    /// Returns the length of a row of pixels in bytes.
+    pub fn pitch(&self) -> uint {
+        unsafe { (*self.raw).pitch as uint }
+    }
+
+    /// Locks the surface and calls `f` with its pixels, row by row, each row
+    /// being `pitch` bytes long.
+    pub fn with_pixels<T>(&mut self, f: |&mut [u8]| -> T) -> SdlResult<T> {
+        unsafe { with_locked_pixels(self.raw, f) }
+    }
+}
+
+impl Drop for Surface {
+    fn drop(&mut self) {
+        unsafe { ffi::surface::SDL_FreeSurface(self.raw as *mut SDL_Surface) }
+    }
+}
+
+/// Locks a surface and calls `f` with its pixels.
+pub unsafe fn with_locked_pixels<T>(raw: *SDL_Surface, f: |&mut [u8]| -> T) -> SdlResult<T> {
+    let (pitch, height) = ((*raw).pitch, (*raw).h);
+    if pitch < 0 || height < 0 {
+        return Err(format!("invalid surface layout: {} rows of {} bytes", height, pitch));
+    }
+    let len = match (pitch as uint).checked_mul(&(height as uint)) {
+        Some(len) => len,
+        None => return Err(format!("surface of {} rows of {} bytes is too large", height, pitch)),
+    };
+    if ffi::surface::SDL_LockSurface(raw as *mut SDL_Surface) != 0 {
+        return Err(get_error());
+    }
+    let result = slice::raw::mut_buf_as_slice((*raw).pixels as *mut u8, len, f);
+    ffi::surface::SDL_UnlockSurface(raw as *mut SDL_Surface);
+    Ok(result)
+}
+
+/// Wraps a buffer of 32 bit pixels, stored as the bytes red, green, blue and
+/// alpha, in a temporary surface and calls `f` with it. The buffer must hold
+/// `height` rows of `pitch` bytes, although the padding after the last row
+/// may be omitted.
+pub fn with_rgba_surface<T>(pixels: &[u8], width: uint, height: uint, pitch: uint,
+                            f: |*SDL_Surface| -> T) -> SdlResult<T> {
+    if width == 0 || height == 0 {
+        return Err(format!("invalid surface size: {}x{}", width, height));
+    }
+    // SDL takes the sizes as C ints
+    let max = i32::MAX as uint;
+    if width > max || height > max || pitch > max {
+        return Err(format!("surface of {}x{} pixels with a pitch of {} is too large",
+                           width, height, pitch));
+    }
+    let row_len = match width.checked_mul(&4) {
+        Some(row_len) if row_len <= pitch => row_len,
+        _ => return Err(format!("pitch of {} bytes is too small for {} pixels", pitch, width)),
+    };
+    let required = match pitch.checked_mul(&(height - 1)).and_then(|len| len.checked_add(&row_len)) {
+        Some(required) => required,
+        None => return Err(format!("surface of {}x{} pixels with a pitch of {} is too large",
+                                   width, height, pitch)),
+    };
+    if pixels.len() < required {
+        return Err(format!("buffer of {} bytes is too small for {}x{} pixels with a pitch of {}",
+                           pixels.len(), width, height, pitch));
+    }
+
+    let (rmask, gmask, bmask, amask) = RGBA_MASKS;
+    let raw = unsafe {
+        // SDL does not write to the pixels unless asked to
+        let data: *c_void = transmute(pixels.as_ptr());
+        ffi::surface::SDL_CreateRGBSurfaceFrom(data, width as c_int, height as c_int, 32,
+                                               pitch as c_int, rmask, gmask, bmask, amask)
+    };
+    if raw.is_null() { return Err(get_error()) }
+
+    // Freed when dropped, after `f` returns
+    let surface = Surface { raw: raw };
+    Ok(f(surface.raw))
+}
@@ -0,0 +1,1804 @@
+// Copyright 2014 The sdl2-rs Developers.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Safe wrappers for windows and the video subsystem.
+
+use collections::HashMap;
+use collections::hashmap::Entries;
+use std::any::{Any, AnyRefExt, AnyMutRefExt};
+use std::cast::transmute;
+use std::cell::Cell;
+use std::kinds::marker;
+use std::num::FromPrimitive;
+use std::ptr;
+use std::str;
+use libc::{c_char, c_float, c_int, c_uint, c_void};
+
+use ffi;
+use ffi::stdinc::SDL_bool;
+use ffi::events::SDL_Event;
+use ffi::surface::SDL_Surface;
+use ffi::thread::{SDL_ThreadID, SDL_threadID};
+use ffi::video::{SDL_DisplayMode, SDL_GLContext, SDL_Window, SDL_WindowFlags};
+use ffi::video::{SDL_GLattr, SDL_GLcontextFlag, SDL_GLprofile};
+use ffi::video::{SDL_WINDOWPOS_CENTERED_MASK, SDL_WINDOWPOS_UNDEFINED_MASK};
+use event::Event;
+use rect::Rect;
+use surface;
+use surface::Surface;
+use {Sdl, SdlResult, get_error};
+
+/// The format of the pixels in a display mode or surface.
+pub type PixelFormat = ffi::pixels::SDL_PixelFormat_;
+
+/// A resolution, refresh rate and pixel format supported by a display.
+#[deriving(Clone, Eq)]
+pub struct DisplayMode {
+    pub format: PixelFormat,
+    pub w: i32,
+    pub h: i32,
+    /// The refresh rate in hertz, or zero if unspecified.
+    pub refresh_rate: i32,
+}
+
+impl DisplayMode {
+    pub fn new(format: PixelFormat, w: i32, h: i32, refresh_rate: i32) -> DisplayMode {
+        DisplayMode { format: format, w: w, h: h, refresh_rate: refresh_rate }
+    }
+
+    fn from_sdl(mode: &SDL_DisplayMode) -> DisplayMode {
+        DisplayMode {
+            format: FromPrimitive::from_u32(mode.format)
+                        .unwrap_or(ffi::pixels::SDL_PIXELFORMAT_UNKNOWN),
+            w: mode.w as i32,
+            h: mode.h as i32,
+            refresh_rate: mode.refresh_rate as i32,
+        }
+    }
+
+    fn to_sdl(&self) -> SDL_DisplayMode {
+        SDL_DisplayMode {
+            format: self.format as u32,
+            w: self.w as c_int,
+            h: self.h as c_int,
+            refresh_rate: self.refresh_rate as c_int,
+            driverdata: ptr::null(),
+        }
+    }
+}
+
+fn empty_sdl_display_mode() -> SDL_DisplayMode {
+    DisplayMode::new(ffi::pixels::SDL_PIXELFORMAT_UNKNOWN, 0, 0, 0).to_sdl()
+}
+
+/// Returns the mode supported by the display that most closely matches the
+/// requested one. Unspecified fields of the request, such as a zero refresh
+/// rate, are filled in from the desktop mode.
+fn closest_display_mode(display_index: c_int, mode: &DisplayMode) -> SdlResult<DisplayMode> {
+    let request = mode.to_sdl();
+    let mut closest = empty_sdl_display_mode();
+    let found = unsafe {
+        ffi::video::SDL_GetClosestDisplayMode(display_index, &request, &mut closest)
+    };
+    if found.is_null() {
+        Err(format!("no display mode matches {}x{} at {}Hz on display {}",
+                    mode.w, mode.h, mode.refresh_rate, display_index))
+    } else {
+        Ok(DisplayMode::from_sdl(&closest))
+    }
+}
+
+/// A display connected to the system, as reported by `VideoSubsystem`.
+pub struct Display {
+    pub index: int,
+    pub name: ~str,
+    /// The area of the desktop covered by the display.
+    pub bounds: Rect,
+}
+
+/// A handle to the video subsystem, used to query the available drivers,
+/// displays and display modes. Most queries fail unless the video subsystem
+/// has been initialised, for example with `sdl2::InitVideo`.
+///
+/// # Example
+///
+/// ~~~
+/// let video = sdl2.video();
+/// for display in video.displays().unwrap().iter() {
+///     println!("{}: {}x{}", display.name, display.bounds.w, display.bounds.h);
+///     for mode in video.display_modes(display.index).unwrap().iter() {
+///         println!("  {}x{} at {}Hz", mode.w, mode.h, mode.refresh_rate);
+///     }
+/// }
+/// ~~~
+#[deriving(Clone)]
+pub struct VideoSubsystem {
+    marker: marker::NoSend,
+}
+
+impl VideoSubsystem {
+    pub fn new(_sdl: &Sdl) -> VideoSubsystem {
+        VideoSubsystem { marker: marker::NoSend }
+    }
+
+    /// Returns the names of the video drivers compiled into SDL.
+    pub fn drivers(&self) -> Vec<~str> {
+        let count = unsafe { ffi::video::SDL_GetNumVideoDrivers() };
+        range(0, count).map(|index| unsafe {
+            str::raw::from_c_str(ffi::video::SDL_GetVideoDriver(index))
+        }).collect()
+    }
+
+    /// Returns the name of the driver in use, or `None` if the video
+    /// subsystem has not been initialised.
+    pub fn current_driver(&self) -> Option<~str> {
+        let name = unsafe { ffi::video::SDL_GetCurrentVideoDriver() };
+        if name.is_null() {
+            None
+        } else {
+            Some(unsafe { str::raw::from_c_str(name) })
+        }
+    }
+
+    pub fn num_displays(&self) -> SdlResult<int> {
+        match unsafe { ffi::video::SDL_GetNumVideoDisplays() } {
+            count if count < 0 => Err(get_error()),
+            count => Ok(count as int),
+        }
+    }
+
+    /// Returns the index, name and bounds of every display.
+    pub fn displays(&self) -> SdlResult<Vec<Display>> {
+        let count = try!(self.num_displays());
+        let mut displays = Vec::with_capacity(count as uint);
+        for index in range(0, count) {
+            displays.push(Display {
+                index: index,
+                name: try!(self.display_name(index)),
+                bounds: try!(self.display_bounds(index)),
+            });
+        }
+        Ok(displays)
+    }
+
+    pub fn display_name(&self, display_index: int) -> SdlResult<~str> {
+        let name = unsafe { ffi::video::SDL_GetDisplayName(display_index as c_int) };
+        if name.is_null() {
+            Err(get_error())
+        } else {
+            Ok(unsafe { str::raw::from_c_str(name) })
+        }
+    }
+
+    /// Returns the area of the desktop covered by the display. The primary
+    /// display is located at `(0, 0)`.
+    pub fn display_bounds(&self, display_index: int) -> SdlResult<Rect> {
+        let mut rect = ffi::rect::SDL_Rect { x: 0, y: 0, w: 0, h: 0 };
+        if unsafe { ffi::video::SDL_GetDisplayBounds(display_index as c_int, &mut rect) } == 0 {
+            Ok(Rect { x: rect.x, y: rect.y, w: rect.w, h: rect.h })
+        } else {
+            Err(get_error())
+        }
+    }
+
+    /// Returns the modes supported by the display, sorted from the largest
+    /// to the smallest.
+    pub fn display_modes(&self, display_index: int) -> SdlResult<Vec<DisplayMode>> {
+        let count = unsafe { ffi::video::SDL_GetNumDisplayModes(display_index as c_int) };
+        if count < 0 { return Err(get_error()) }
+
+        let mut modes = Vec::with_capacity(count as uint);
+        for mode_index in range(0, count) {
+            let mut mode = empty_sdl_display_mode();
+            if unsafe { ffi::video::SDL_GetDisplayMode(display_index as c_int, mode_index, &mut mode) } != 0 {
+                return Err(get_error());
+            }
+            modes.push(DisplayMode::from_sdl(&mode));
+        }
+        Ok(modes)
+    }
+
+    /// Returns the mode of the display when the desktop is shown, which is
+    /// unaffected by windows in exclusive fullscreen.
+    pub fn desktop_display_mode(&self, display_index: int) -> SdlResult<DisplayMode> {
+        let mut mode = empty_sdl_display_mode();
+        if unsafe { ffi::video::SDL_GetDesktopDisplayMode(display_index as c_int, &mut mode) } == 0 {
+            Ok(DisplayMode::from_sdl(&mode))
+        } else {
+            Err(get_error())
+        }
+    }
+
+    /// Returns the mode the display is currently using.
+    pub fn current_display_mode(&self, display_index: int) -> SdlResult<DisplayMode> {
+        let mut mode = empty_sdl_display_mode();
+        if unsafe { ffi::video::SDL_GetCurrentDisplayMode(display_index as c_int, &mut mode) } == 0 {
+            Ok(DisplayMode::from_sdl(&mode))
+        } else {
+            Err(get_error())
+        }
+    }
+
+    /// Returns the mode supported by the display that most closely matches
+    /// the requested one, or an error if none is close enough.
+    pub fn closest_display_mode(&self, display_index: int, mode: &DisplayMode) -> SdlResult<DisplayMode> {
+        closest_display_mode(display_index as c_int, mode)
+    }
+}
+
+/// A gamma correction table for each colour channel, mapping each of the
+/// 256 input levels to an output level between `0` and `65535`.
+pub struct GammaRamp {
+    pub red: [u16, ..256],
+    pub green: [u16, ..256],
+    pub blue: [u16, ..256],
+}
+
+impl GammaRamp {
+    /// Returns the ramp that leaves colours unchanged.
+    pub fn identity() -> GammaRamp {
+        GammaRamp::from_curve(|level| level)
+    }
+
+    /// Calculates the ramp for a gamma value, applied to every channel. A
+    /// gamma of `1.0` leaves colours unchanged, while `0.0` makes them black.
+    pub fn from_gamma(gamma: f32) -> SdlResult<GammaRamp> {
+        // SDL converts the resulting levels to integers, which is undefined
+        // for NaN
+        if gamma < 0.0 || !gamma.is_finite() {
+            return Err(format!("invalid gamma value: {}", gamma));
+        }
+        let mut channel = [0u16, ..256];
+        unsafe { ffi::pixels::SDL_CalculateGammaRamp(gamma as c_float, channel.as_mut_ptr()) };
+        Ok(GammaRamp { red: channel, green: channel, blue: channel })
+    }
+
+    /// Builds the ramp from a curve applied to every channel. The curve maps
+    /// input levels between `0.0` and `1.0` to output levels in the same
+    /// range, and its results are clamped to that range. NaN results are
+    /// treated as `0.0`.
+    pub fn from_curve(curve: |f32| -> f32) -> GammaRamp {
+        let mut channel = [0u16, ..256];
+        for (i, level) in channel.mut_iter().enumerate() {
+            let output = match curve(i as f32 / 255.0) {
+                output if output.is_nan() => 0.0,
+                output if output < 0.0 => 0.0,
+                output if output > 1.0 => 1.0,
+                output => output,
+            };
+            *level = (output * 65535.0 + 0.5) as u16;
+        }
+        GammaRamp { red: channel, green: channel, blue: channel }
+    }
+}
+
+/// Restores the gamma ramp of a window's display when dropped, including
+/// when the task fails. Created with `Window::apply_gamma_ramp` or
+/// `Window::preserve_gamma_ramp`.
+pub struct GammaRampGuard<'a> {
+    window: &'a Window,
+    original: GammaRamp,
+}
+
+impl<'a> GammaRampGuard<'a> {
+    /// Returns the ramp that will be restored.
+    pub fn original<'b>(&'b self) -> &'b GammaRamp {
+        &self.original
+    }
+}
+
+#[unsafe_destructor]
+impl<'a> Drop for GammaRampGuard<'a> {
+    fn drop(&mut self) {
+        // Errors cannot be reported from here, and the ramp was accepted
+        // when it was read, so the result is ignored.
+        let _ = self.window.set_gamma_ramp(&self.original);
+    }
+}
+
+/// Driver selection
+impl VideoSubsystem {
+    /// Initialises video with the named driver, such as `"dummy"`, or with
+    /// the driver SDL would choose by default if `None` is given. This is an
+    /// alternative to initialising SDL with `sdl2::InitVideo`, and shuts
+    /// down any driver already in use. If the name is not one of `drivers()`,
+    /// the error lists the available drivers. Fails while any `Window` is
+    /// alive, as shutting down the driver would destroy it.
+    ///
+    /// # Example
+    ///
+    /// ~~~
+    /// let video = sdl2.video();
+    /// video.init_driver(Some("dummy")).unwrap();
+    /// assert_eq!(video.current_driver(), Some(~"dummy"));
+    /// ~~~
+    pub fn init_driver(&self, driver: Option<&str>) -> SdlResult<()> {
+        try!(check_no_live_windows());
+        let result = match driver {
+            Some(name) => {
+                let drivers = self.drivers();
+                if !drivers.iter().any(|d| d.as_slice() == name) {
+                    return Err(format!("unknown video driver `{}`, the available drivers are: {}",
+                                       name, drivers.as_slice().connect(", ")));
+                }
+                name.with_c_str(|name| unsafe { ffi::video::SDL_VideoInit(name) })
+            },
+            None => unsafe { ffi::video::SDL_VideoInit(ptr::null()) },
+        };
+        if result == 0 { Ok(()) } else { Err(get_error()) }
+    }
+
+    /// Shuts down the video driver started with `init_driver`. Fails while
+    /// any `Window` is alive, as SDL would destroy it.
+    pub fn quit_driver(&self) -> SdlResult<()> {
+        try!(check_no_live_windows());
+        unsafe { ffi::video::SDL_VideoQuit() };
+        Ok(())
+    }
+}
+
+// Windows cannot leave the main task, so this is never accessed
+// concurrently.
+static mut LIVE_WINDOWS: uint = 0;
+
+fn check_no_live_windows() -> SdlResult<()> {
+    match unsafe { LIVE_WINDOWS } {
+        0 => Ok(()),
+        count => Err(format!("the video driver cannot be changed while {} windows are alive", count)),
+    }
+}
+
+/// Screen saver
+impl VideoSubsystem {
+    pub fn is_screen_saver_enabled(&self) -> bool {
+        unsafe { ffi::video::SDL_IsScreenSaverEnabled().to_bool() }
+    }
+
+    pub fn enable_screen_saver(&self) {
+        unsafe { ffi::video::SDL_EnableScreenSaver() }
+    }
+
+    pub fn disable_screen_saver(&self) {
+        unsafe { ffi::video::SDL_DisableScreenSaver() }
+    }
+
+    /// Disables the screen saver until the returned inhibitor is dropped.
+    /// Inhibitors can be nested: the screen saver is only re-enabled when
+    /// the last one is dropped, and only if it was enabled before the first
+    /// one was created.
+    ///
+    /// # Example
+    ///
+    /// ~~~
+    /// {
+    ///     let _inhibitor = sdl2.video().inhibit_screen_saver();
+    ///     play_cutscene();
+    /// } // The screen saver is restored here
+    /// ~~~
+    pub fn inhibit_screen_saver(&self) -> ScreenSaverInhibitor {
+        unsafe {
+            if SCREEN_SAVER_INHIBITORS == 0 {
+                SCREEN_SAVER_WAS_ENABLED = self.is_screen_saver_enabled();
+                self.disable_screen_saver();
+            }
+            SCREEN_SAVER_INHIBITORS += 1;
+        }
+        ScreenSaverInhibitor { marker: marker::NoSend }
+    }
+}
+
+// Inhibitors cannot leave the main task, so these are never accessed
+// concurrently.
+static mut SCREEN_SAVER_INHIBITORS: uint = 0;
+static mut SCREEN_SAVER_WAS_ENABLED: bool = false;
+
+/// Keeps the screen saver disabled until dropped. Created with
+/// `VideoSubsystem::inhibit_screen_saver`.
+pub struct ScreenSaverInhibitor {
+    marker: marker::NoSend,
+}
+
+impl Drop for ScreenSaverInhibitor {
+    fn drop(&mut self) {
+        unsafe {
+            SCREEN_SAVER_INHIBITORS -= 1;
+            if SCREEN_SAVER_INHIBITORS == 0 && SCREEN_SAVER_WAS_ENABLED {
+                ffi::video::SDL_EnableScreenSaver();
+            }
+        }
+    }
+}
+
+/// How a window occupies its display.
+#[deriving(Clone, Eq)]
+pub enum FullscreenMode {
+    Windowed,
+    /// Fullscreen, changing the video mode of the display to the window's
+    /// display mode.
+    FullscreenExclusive,
+    /// Fullscreen at the resolution of the desktop, without changing the
+    /// video mode.
+    FullscreenDesktop,
+}
+
+/// The position of a window along one axis of the desktop.
+#[deriving(Clone, Eq)]
+pub enum WindowPos {
+    /// Let the window manager choose, on the display with the given index.
+    PosUndefined(int),
+    /// Centre the window on the display with the given index.
+    PosCentered(int),
+    /// Place the window at the given coordinate.
+    Positioned(i32),
+}
+
+impl WindowPos {
+    fn to_sdl(&self) -> c_int {
+        match *self {
+            PosUndefined(display)   => SDL_WINDOWPOS_UNDEFINED_DISPLAY!(display as c_int),
+            PosCentered(display)    => SDL_WINDOWPOS_CENTERED_DISPLAY!(display as c_int),
+            Positioned(pos)         => pos as c_int,
+        }
+    }
+}
+
+/// Describes a window to be created. The window is placed wherever the
+/// window manager chooses on the first display unless a position is given.
+///
+/// # Example
+///
+/// ~~~
+/// let window = sdl2::video::WindowBuilder::new("Hello", 800, 600)
+///     .position_centered()
+///     .resizable()
+///     .build(&sdl2);
+///
+/// match window {
+///     Ok(window) => { /* ... */ },
+///     Err(msg) => fail!("could not create the window: {}", msg),
+/// }
+/// ~~~
+#[deriving(Clone)]
+pub struct WindowBuilder {
+    title: ~str,
+    x: WindowPos,
+    y: WindowPos,
+    width: i32,
+    height: i32,
+    flags: SDL_WindowFlags,
+}
+
+impl WindowBuilder {
+    /// Starts describing a window with the given title and size.
+    pub fn new(title: &str, width: i32, height: i32) -> WindowBuilder {
+        WindowBuilder {
+            title: title.to_owned(),
+            x: PosUndefined(0),
+            y: PosUndefined(0),
+            width: width,
+            height: height,
+            flags: SDL_WindowFlags::empty(),
+        }
+    }
+
+    /// Sets the position of the window.
+    pub fn position(mut self, x: WindowPos, y: WindowPos) -> WindowBuilder {
+        self.x = x;
+        self.y = y;
+        self
+    }
+
+    /// Centres the window on the first display.
+    pub fn position_centered(self) -> WindowBuilder {
+        self.position(PosCentered(0), PosCentered(0))
+    }
+
+    fn with_flag(mut self, flag: SDL_WindowFlags) -> WindowBuilder {
+        self.flags = self.flags | flag;
+        self
+    }
+
+    /// Makes the window fullscreen at its requested size.
+    pub fn fullscreen(self) -> WindowBuilder { self.with_flag(ffi::video::SDL_WINDOW_FULLSCREEN) }
+
+    /// Makes the window usable with an OpenGL context.
+    pub fn opengl(self) -> WindowBuilder { self.with_flag(ffi::video::SDL_WINDOW_OPENGL) }
+
+    /// Creates the window without showing it.
+    pub fn hidden(self) -> WindowBuilder { self.with_flag(ffi::video::SDL_WINDOW_HIDDEN) }
+
+    /// Creates the window without any decoration.
+    pub fn borderless(self) -> WindowBuilder { self.with_flag(ffi::video::SDL_WINDOW_BORDERLESS) }
+
+    /// Allows the user to resize the window.
+    pub fn resizable(self) -> WindowBuilder { self.with_flag(ffi::video::SDL_WINDOW_RESIZABLE) }
+
+    /// Creates the window minimized.
+    pub fn minimized(self) -> WindowBuilder { self.with_flag(ffi::video::SDL_WINDOW_MINIMIZED) }
+
+    /// Creates the window maximized.
+    pub fn maximized(self) -> WindowBuilder { self.with_flag(ffi::video::SDL_WINDOW_MAXIMIZED) }
+
+    /// Confines the mouse to the window once it has input focus.
+    pub fn input_grabbed(self) -> WindowBuilder { self.with_flag(ffi::video::SDL_WINDOW_INPUT_GRABBED) }
+
+    /// Creates the window, returning the error reported by SDL on failure.
+    pub fn build(&self, _sdl: &Sdl) -> SdlResult<Window> {
+        let raw = self.title.with_c_str(|title| unsafe {
+            ffi::video::SDL_CreateWindow(title,
+                                         self.x.to_sdl(), self.y.to_sdl(),
+                                         self.width as c_int, self.height as c_int,
+                                         self.flags.bits())
+        });
+        if raw.is_null() {
+            Err(get_error())
+        } else {
+            unsafe { LIVE_WINDOWS += 1 };
+            let resizes = ~ResizeCounter {
+                window_id: unsafe { ffi::video::SDL_GetWindowID(raw) },
+                thread: unsafe { SDL_ThreadID() },
+                count: Cell::new(0),
+            };
+            unsafe { ffi::events::SDL_AddEventWatch(count_resizes, resizes.as_userdata()) };
+            Ok(Window {
+                raw: raw,
+                windowed: Cell::new(None),
+                resizes: resizes,
+                data: HashMap::new(),
+                marker: marker::NoSend,
+            })
+        }
+    }
+}
+
+/// A window, which is destroyed when dropped. Windows are created with a
+/// `WindowBuilder`, and like `Sdl` cannot be sent to other tasks.
+pub struct Window {
+    raw: *SDL_Window,
+    /// The geometry to restore when leaving fullscreen.
+    windowed: Cell<Option<Rect>>,
+    resizes: ~ResizeCounter,
+    data: HashMap<~str, ~Any:Send>,
+    marker: marker::NoSend,
+}
+
+/// Counts the size changes of a window, which SDL reports to it through an
+/// event watch. Each change replaces the window surface.
+struct ResizeCounter {
+    window_id: u32,
+    thread: SDL_threadID,
+    count: Cell<uint>,
+}
+
+impl ResizeCounter {
+    fn as_userdata(&self) -> *c_void {
+        self as *ResizeCounter as *c_void
+    }
+}
+
+extern "C" fn count_resizes(userdata: *c_void, raw: *SDL_Event) -> c_int {
+    let resizes: &ResizeCounter = unsafe { transmute(userdata) };
+    let raw = unsafe { &*raw };
+    // Windows are resized on the thread that created them, and the counter
+    // must not be touched from any other
+    if unsafe { SDL_ThreadID() } == resizes.thread && raw.event_type.is_window() {
+        let data = unsafe { raw.window() };
+        if data.windowID == resizes.window_id
+        && data.event == ffi::video::SDL_WINDOWEVENT_SIZE_CHANGED as u8 {
+            resizes.count.set(resizes.count.get() + 1);
+        }
+    }
+    1
+}
+
+impl Window {
+    /// Returns the underlying SDL window, for use with the `ffi` functions.
+    /// The pointer is only valid for as long as the window is alive.
+    pub fn raw(&self) -> *SDL_Window {
+        self.raw
+    }
+
+    /// Returns the id that SDL uses to identify the window in events.
+    pub fn id(&self) -> u32 {
+        unsafe { ffi::video::SDL_GetWindowID(self.raw) }
+    }
+
+    /// Returns the state of the window, such as whether it is shown,
+    /// fullscreen or has input focus.
+    pub fn flags(&self) -> SDL_WindowFlags {
+        let bits = unsafe { ffi::video::SDL_GetWindowFlags(self.raw) };
+        SDL_WindowFlags::from_bits_truncate(bits)
+    }
+
+    /// Returns the index of the display that contains the centre of the
+    /// window.
+    pub fn display_index(&self) -> SdlResult<int> {
+        match unsafe { ffi::video::SDL_GetWindowDisplayIndex(self.raw) } {
+            index if index < 0 => Err(get_error()),
+            index => Ok(index as int),
+        }
+    }
+}
+
+/// Geometry
+impl Window {
+    pub fn title(&self) -> ~str {
+        unsafe { str::raw::from_c_str(ffi::video::SDL_GetWindowTitle(self.raw)) }
+    }
+
+    pub fn set_title(&self, title: &str) {
+        title.with_c_str(|title| unsafe {
+            ffi::video::SDL_SetWindowTitle(self.raw, title)
+        })
+    }
+
+    pub fn position(&self) -> (i32, i32) {
+        let (mut x, mut y) = (0, 0);
+        unsafe { ffi::video::SDL_GetWindowPosition(self.raw, &mut x, &mut y) };
+        (x, y)
+    }
+
+    pub fn set_position(&self, x: WindowPos, y: WindowPos) {
+        unsafe { ffi::video::SDL_SetWindowPosition(self.raw, x.to_sdl(), y.to_sdl()) }
+    }
+
+    /// Returns the size of the window's client area.
+    pub fn size(&self) -> (i32, i32) {
+        let (mut w, mut h) = (0, 0);
+        unsafe { ffi::video::SDL_GetWindowSize(self.raw, &mut w, &mut h) };
+        (w, h)
+    }
+
+    pub fn set_size(&self, w: i32, h: i32) {
+        unsafe { ffi::video::SDL_SetWindowSize(self.raw, w as c_int, h as c_int) }
+    }
+
+    pub fn minimum_size(&self) -> (i32, i32) {
+        let (mut w, mut h) = (0, 0);
+        unsafe { ffi::video::SDL_GetWindowMinimumSize(self.raw, &mut w, &mut h) };
+        (w, h)
+    }
+
+    pub fn set_minimum_size(&self, w: i32, h: i32) {
+        unsafe { ffi::video::SDL_SetWindowMinimumSize(self.raw, w as c_int, h as c_int) }
+    }
+
+    pub fn maximum_size(&self) -> (i32, i32) {
+        let (mut w, mut h) = (0, 0);
+        unsafe { ffi::video::SDL_GetWindowMaximumSize(self.raw, &mut w, &mut h) };
+        (w, h)
+    }
+
+    pub fn set_maximum_size(&self, w: i32, h: i32) {
+        unsafe { ffi::video::SDL_SetWindowMaximumSize(self.raw, w as c_int, h as c_int) }
+    }
+}
+
+/// Appearance and input
+impl Window {
+    /// Adds or removes the window's decoration. This has no effect on
+    /// fullscreen windows.
+    pub fn set_bordered(&self, bordered: bool) {
+        unsafe { ffi::video::SDL_SetWindowBordered(self.raw, SDL_bool::from_bool(bordered)) }
+    }
+
+    /// Returns `true` if the mouse is confined to the window.
+    pub fn grab(&self) -> bool {
+        unsafe { ffi::video::SDL_GetWindowGrab(self.raw).to_bool() }
+    }
+
+    pub fn set_grab(&self, grabbed: bool) {
+        unsafe { ffi::video::SDL_SetWindowGrab(self.raw, SDL_bool::from_bool(grabbed)) }
+    }
+
+    /// Returns the brightness of the display that owns the window, where
+    /// `1.0` is normal.
+    pub fn brightness(&self) -> f32 {
+        unsafe { ffi::video::SDL_GetWindowBrightness(self.raw) as f32 }
+    }
+
+    /// Sets the brightness of the display that owns the window, where `0.0`
+    /// is completely dark and `1.0` is normal.
+    pub fn set_brightness(&self, brightness: f32) -> SdlResult<()> {
+        if unsafe { ffi::video::SDL_SetWindowBrightness(self.raw, brightness as c_float) } == 0 {
+            Ok(())
+        } else {
+            Err(get_error())
+        }
+    }
+}
+
+/// Visibility
+impl Window {
+    pub fn show(&self) {
+        unsafe { ffi::video::SDL_ShowWindow(self.raw) }
+    }
+
+    pub fn hide(&self) {
+        unsafe { ffi::video::SDL_HideWindow(self.raw) }
+    }
+
+    /// Raises the window above other windows and gives it input focus.
+    pub fn raise(&self) {
+        unsafe { ffi::video::SDL_RaiseWindow(self.raw) }
+    }
+
+    pub fn maximize(&self) {
+        unsafe { ffi::video::SDL_MaximizeWindow(self.raw) }
+    }
+
+    pub fn minimize(&self) {
+        unsafe { ffi::video::SDL_MinimizeWindow(self.raw) }
+    }
+
+    /// Restores the size and position of a minimized or maximized window.
+    pub fn restore(&self) {
+        unsafe { ffi::video::SDL_RestoreWindow(self.raw) }
+    }
+}
+
+/// Fullscreen
+impl Window {
+    pub fn fullscreen_mode(&self) -> FullscreenMode {
+        let flags = self.flags();
+        if flags.contains(ffi::video::SDL_WINDOW_FULLSCREEN_DESKTOP) {
+            FullscreenDesktop
+        } else if flags.contains(ffi::video::SDL_WINDOW_FULLSCREEN) {
+            FullscreenExclusive
+        } else {
+            Windowed
+        }
+    }
+
+    /// Switches the window between windowed and fullscreen. The position and
+    /// size of the window are remembered when it leaves windowed mode, and
+    /// restored when it returns.
+    pub fn set_fullscreen(&self, mode: FullscreenMode) -> SdlResult<()> {
+        let current = self.fullscreen_mode();
+        if current == mode { return Ok(()) }
+
+        if current == Windowed {
+            let ((x, y), (w, h)) = (self.position(), self.size());
+            self.windowed.set(Some(Rect { x: x, y: y, w: w, h: h }));
+        }
+
+        let flags = match mode {
+            Windowed            => SDL_WindowFlags::empty(),
+            FullscreenExclusive => ffi::video::SDL_WINDOW_FULLSCREEN,
+            FullscreenDesktop   => ffi::video::SDL_WINDOW_FULLSCREEN_DESKTOP,
+        };
+        if unsafe { ffi::video::SDL_SetWindowFullscreen(self.raw, flags.bits()) } != 0 {
+            return Err(get_error());
+        }
+
+        if mode == Windowed {
+            match self.windowed.get() {
+                Some(rect) => {
+                    self.set_size(rect.w, rect.h);
+                    self.set_position(Positioned(rect.x), Positioned(rect.y));
+                    self.windowed.set(None);
+                },
+                None => {},
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the display mode used when the window is in exclusive
+    /// fullscreen.
+    pub fn display_mode(&self) -> SdlResult<DisplayMode> {
+        let mut mode = empty_sdl_display_mode();
+        if unsafe { ffi::video::SDL_GetWindowDisplayMode(self.raw, &mut mode) } == 0 {
+            Ok(DisplayMode::from_sdl(&mode))
+        } else {
+            Err(get_error())
+        }
+    }
+
+    /// Sets the display mode used when the window is in exclusive
+    /// fullscreen. If `None` is given, the window's size and the desktop's
+    /// format and refresh rate are used.
+    pub fn set_display_mode(&self, mode: Option<&DisplayMode>) -> SdlResult<()> {
+        let result = match mode {
+            Some(mode) => unsafe { ffi::video::SDL_SetWindowDisplayMode(self.raw, &mode.to_sdl()) },
+            None => unsafe { ffi::video::SDL_SetWindowDisplayMode(self.raw, ptr::null()) },
+        };
+        if result == 0 { Ok(()) } else { Err(get_error()) }
+    }
+
+    /// Switches the window to exclusive fullscreen, using the mode of its
+    /// current display that most closely matches the requested one. Returns
+    /// the mode that was chosen, or an error if no mode could be matched.
+    ///
+    /// # Example
+    ///
+    /// ~~~
+    /// let request = sdl2::video::DisplayMode::new(
+    ///     sdl2::ffi::pixels::SDL_PIXELFORMAT_UNKNOWN, 1920, 1080, 60);
+    /// match window.set_fullscreen_display_mode(&request) {
+    ///     Ok(mode) => println!("running at {}x{}", mode.w, mode.h),
+    ///     Err(msg) => println!("staying windowed: {}", msg),
+    /// }
+    /// ~~~
+    pub fn set_fullscreen_display_mode(&self, mode: &DisplayMode) -> SdlResult<DisplayMode> {
+        let display_index = try!(self.display_index());
+        let closest = try!(closest_display_mode(display_index as c_int, mode));
+        try!(self.set_display_mode(Some(&closest)));
+        try!(self.set_fullscreen(FullscreenExclusive));
+        Ok(closest)
+    }
+}
+
+/// Gamma correction
+impl Window {
+    /// Returns the gamma ramp of the display that owns the window.
+    pub fn gamma_ramp(&self) -> SdlResult<GammaRamp> {
+        let mut ramp = GammaRamp { red: [0, ..256], green: [0, ..256], blue: [0, ..256] };
+        let result = unsafe {
+            ffi::video::SDL_GetWindowGammaRamp(self.raw, ramp.red.as_mut_ptr(),
+                                               ramp.green.as_mut_ptr(),
+                                               ramp.blue.as_mut_ptr())
+        };
+        if result == 0 { Ok(ramp) } else { Err(get_error()) }
+    }
+
+    /// Sets the gamma ramp of the display that owns the window. The ramp is
+    /// left in place after the program exits; use `apply_gamma_ramp` to
+    /// have it restored automatically.
+    pub fn set_gamma_ramp(&self, ramp: &GammaRamp) -> SdlResult<()> {
+        let result = unsafe {
+            ffi::video::SDL_SetWindowGammaRamp(self.raw, ramp.red.as_ptr(),
+                                               ramp.green.as_ptr(),
+                                               ramp.blue.as_ptr())
+        };
+        if result == 0 { Ok(()) } else { Err(get_error()) }
+    }
+
+    /// Sets the gamma ramp of the display that owns the window, restoring
+    /// the current one when the returned guard is dropped.
+    ///
+    /// # Example
+    ///
+    /// ~~~
+    /// let ramp = sdl2::video::GammaRamp::from_gamma(1.5).unwrap();
+    /// let _gamma = window.apply_gamma_ramp(&ramp).unwrap();
+    /// // The original ramp is restored when `_gamma` goes out of scope
+    /// ~~~
+    pub fn apply_gamma_ramp<'a>(&'a self, ramp: &GammaRamp) -> SdlResult<GammaRampGuard<'a>> {
+        let guard = try!(self.preserve_gamma_ramp());
+        try!(self.set_gamma_ramp(ramp));
+        Ok(guard)
+    }
+
+    /// Saves the current gamma ramp, restoring it when the returned guard is
+    /// dropped. This also undoes changes made with `set_brightness`.
+    pub fn preserve_gamma_ramp<'a>(&'a self) -> SdlResult<GammaRampGuard<'a>> {
+        let original = try!(self.gamma_ramp());
+        Ok(GammaRampGuard { window: self, original: original })
+    }
+}
+
+/// Icon
+impl Window {
+    /// Sets the icon shown for the window by the window manager. SDL keeps
+    /// its own copy, so the surface can be dropped afterwards.
+    pub fn set_icon(&self, icon: &Surface) {
+        unsafe { ffi::video::SDL_SetWindowIcon(self.raw, icon.raw()) }
+    }
+
+    /// Sets the icon from a buffer of 32 bit pixels, stored as the bytes red,
+    /// green, blue and alpha. The buffer must hold `height` rows of `pitch`
+    /// bytes, and an error is returned if it is too small.
+    ///
+    /// # Example
+    ///
+    /// ~~~
+    /// // A 2x2 icon with red, green, blue and transparent pixels
+    /// let pixels = [255u8, 0, 0, 255,   0, 255, 0, 255,
+    ///               0, 0, 255, 255,     0, 0, 0, 0];
+    /// window.set_icon_rgba(pixels, 2, 2, 8).unwrap();
+    /// ~~~
+    pub fn set_icon_rgba(&self, pixels: &[u8], width: uint, height: uint, pitch: uint) -> SdlResult<()> {
+        surface::with_rgba_surface(pixels, width, height, pitch, |icon| unsafe {
+            ffi::video::SDL_SetWindowIcon(self.raw, icon)
+        })
+    }
+}
+
+/// Software rendering
+impl Window {
+    /// Returns the surface used to draw into the window from the CPU,
+    /// creating it if needed. This works with every video driver, including
+    /// `dummy`, but cannot be combined with OpenGL rendering on the same
+    /// window.
+    ///
+    /// # Example
+    ///
+    /// ~~~
+    /// let mut surface = window.surface().unwrap();
+    /// surface.with_pixels(|pixels| {
+    ///     for byte in pixels.mut_iter() { *byte = 0xFF }
+    /// }).unwrap();
+    /// surface.update().unwrap();
+    /// ~~~
+    pub fn surface<'a>(&'a mut self) -> SdlResult<WindowSurface<'a>> {
+        let raw = unsafe { ffi::video::SDL_GetWindowSurface(self.raw) };
+        if raw.is_null() {
+            Err(get_error())
+        } else {
+            let size = self.size();
+            let resizes = self.resizes.count.get();
+            Ok(WindowSurface { window: self, raw: raw, size: size, resizes: resizes })
+        }
+    }
+}
+
+/// OpenGL
+impl Window {
+    /// Creates an OpenGL context for the window, which must have been built
+    /// with `WindowBuilder::opengl`, and makes it current. The context
+    /// borrows the window, so cannot outlive it.
+    ///
+    /// # Example
+    ///
+    /// ~~~
+    /// let window = sdl2::video::WindowBuilder::new("GL", 800, 600)
+    ///     .opengl()
+    ///     .build(&sdl2).unwrap();
+    /// let context = window.gl_create_context().unwrap();
+    /// sdl2.video().gl_set_swap_interval(sdl2::video::VSync).unwrap();
+    /// loop {
+    ///     // draw with OpenGL
+    ///     context.swap_window();
+    /// }
+    /// ~~~
+    pub fn gl_create_context<'a>(&'a self) -> SdlResult<GLContext<'a>> {
+        let raw = unsafe { ffi::video::SDL_GL_CreateContext(self.raw) };
+        if raw.is_null() {
+            Err(get_error())
+        } else {
+            Ok(GLContext { raw: raw, window: self })
+        }
+    }
+
+    /// Returns `true` if the window is the target of the OpenGL context that
+    /// is current on this thread.
+    pub fn is_gl_current(&self) -> bool {
+        unsafe { ffi::video::SDL_GL_GetCurrentWindow() == self.raw }
+    }
+
+    /// Presents the frame drawn with OpenGL, if the window is double
+    /// buffered.
+    pub fn gl_swap_window(&self) {
+        unsafe { ffi::video::SDL_GL_SwapWindow(self.raw) }
+    }
+}
+
+/// User data
+impl Window {
+    /// Attaches a value to the window under the given key, replacing and
+    /// dropping any previous value. The window owns the value, and drops it
+    /// when the window is destroyed.
+    ///
+    /// # Example
+    ///
+    /// ~~~
+    /// window.set_data("player", 1u);
+    ///
+    /// assert_eq!(window.data::<uint>("player"), Some(&1u));
+    /// assert_eq!(window.data::<int>("player"), None);
+    /// ~~~
+    pub fn set_data<T: Send>(&mut self, key: &str, value: T) {
+        self.data.insert(key.to_owned(), ~value as ~Any:Send);
+    }
+
+    /// Returns the value stored under the key, or `None` if there is no value
+    /// or it is not of type `T`.
+    pub fn data<'a, T: 'static>(&'a self, key: &str) -> Option<&'a T> {
+        match self.data.find(&key.to_owned()) {
+            Some(value) => value.as_ref::<T>(),
+            None => None,
+        }
+    }
+
+    /// Returns the value stored under the key mutably, or `None` if there is
+    /// no value or it is not of type `T`.
+    pub fn data_mut<'a, T: 'static>(&'a mut self, key: &str) -> Option<&'a mut T> {
+        match self.data.find_mut(&key.to_owned()) {
+            Some(value) => value.as_mut::<T>(),
+            None => None,
+        }
+    }
+
+    /// Drops the value stored under the key, returning `false` if there was
+    /// none.
+    pub fn remove_data(&mut self, key: &str) -> bool {
+        self.data.pop(&key.to_owned()).is_some()
+    }
+}
+
+impl Drop for Window {
+    fn drop(&mut self) {
+        unsafe {
+            // Never leave the current OpenGL context targeting a destroyed
+            // window
+            if ffi::video::SDL_GL_GetCurrentWindow() == self.raw {
+                ffi::video::SDL_GL_MakeCurrent(ptr::null(), ptr::null());
+            }
+            ffi::events::SDL_DelEventWatch(count_resizes, self.resizes.as_userdata());
+            ffi::video::SDL_DestroyWindow(self.raw);
+            LIVE_WINDOWS -= 1;
+        }
+    }
+}
+
+/// The surface of a window, borrowed from the window with `Window::surface`.
+/// SDL replaces the surface when the window is resized, after which this
+/// one can no longer be used, and its methods return an error. A new surface
+/// must then be borrowed.
+///
+/// Resizes are detected through the window events that SDL sends as they
+/// happen. If `SDL_WINDOWEVENT` is disabled, or an event filter drops the
+/// events, only a change in size since the surface was borrowed is detected.
+///
+/// # Example
+///
+/// The dummy video driver keeps its surfaces in system memory, so this works
+/// without a display:
+///
+/// ~~~
+/// sdl2.init_video(Some("dummy")).unwrap();
+/// let mut window = sdl2::video::WindowBuilder::new("surface", 64, 48).build(&sdl2).unwrap();
+/// let raw = window.raw();
+/// let surface = window.surface().unwrap();
+/// assert_eq!(surface.width(), Ok(64));
+///
+/// // Resized and back again behind the surface's back, as by the user.
+/// // The window events that report this mark the surface as replaced.
+/// unsafe {
+///     sdl2::ffi::video::SDL_SetWindowSize(raw, 128, 96);
+///     sdl2::ffi::video::SDL_SetWindowSize(raw, 64, 48);
+/// }
+/// assert!(!surface.is_valid());
+/// assert!(surface.update().is_err());
+/// ~~~
+pub struct WindowSurface<'a> {
+    window: &'a mut Window,
+    raw: *SDL_Surface,
+    size: (i32, i32),
+    // The number of times the window had been resized when borrowed
+    resizes: uint,
+}
+
+impl<'a> WindowSurface<'a> {
+    /// Returns the underlying SDL surface, for use with the `ffi` functions.
+    /// The surface is owned by the window and must not be freed.
+    pub fn raw(&self) -> SdlResult<*SDL_Surface> {
+        try!(self.check_valid());
+        Ok(self.raw)
+    }
+
+    /// Returns `false` if the window has been resized since the surface was
+    /// borrowed, even if it has since been resized back.
+    pub fn is_valid(&self) -> bool {
+        self.window.resizes.count.get() == self.resizes && self.window.size() == self.size
+    }
+
+    fn check_valid(&self) -> SdlResult<()> {
+        if self.is_valid() {
+            Ok(())
+        } else {
+            Err(~"the window surface was invalidated by a resize")
+        }
+    }
+
+    pub fn width(&self) -> SdlResult<uint> {
+        try!(self.check_valid());
+        Ok(unsafe { (*self.raw).w as uint })
+    }
+
+    pub fn height(&self) -> SdlResult<uint> {
+        try!(self.check_valid());
+        Ok(unsafe { (*self.raw).h as uint })
+    }
+
+    /// Returns the length of a row of pixels in bytes.
+    pub fn pitch(&self) -> SdlResult<uint> {
+        try!(self.check_valid());
+        Ok(unsafe { (*self.raw).pitch as uint })
+    }
+
+    pub fn format(&self) -> PixelFormat {
+        let format = unsafe { ffi::video::SDL_GetWindowPixelFormat(self.window.raw) };
+        FromPrimitive::from_u32(format).unwrap_or(ffi::pixels::SDL_PIXELFORMAT_UNKNOWN)
+    }
+
+    /// Locks the surface and calls `f` with its pixels, row by row, each row
+    /// being `pitch` bytes long.
+    pub fn with_pixels<T>(&mut self, f: |&mut [u8]| -> T) -> SdlResult<T> {
+        try!(self.check_valid());
+        unsafe { surface::with_locked_pixels(self.raw, f) }
+    }
+
+    /// Copies the whole surface to the window.
+    pub fn update(&self) -> SdlResult<()> {
+        try!(self.check_valid());
+        if unsafe { ffi::video::SDL_UpdateWindowSurface(self.window.raw) } == 0 {
+            Ok(())
+        } else {
+            Err(get_error())
+        }
+    }
+
+    /// Copies the areas of the surface covered by the rectangles to the
+    /// window.
+    pub fn update_rects(&self, rects: &[Rect]) -> SdlResult<()> {
+        try!(self.check_valid());
+        let result = unsafe {
+            ffi::video::SDL_UpdateWindowSurfaceRects(self.window.raw,
+                                                     transmute(rects.as_ptr()),
+                                                     rects.len() as c_int)
+        };
+        if result == 0 { Ok(()) } else { Err(get_error()) }
+    }
+}
+
+/// How buffer swaps are synchronised with the vertical retrace of the
+/// display.
+#[deriving(Clone, Eq)]
+pub enum SwapInterval {
+    /// Swap immediately, which may cause tearing.
+    Immediate,
+    /// Wait for the vertical retrace before swapping.
+    VSync,
+    /// Wait for the vertical retrace, unless it was already missed for the
+    /// frame, in which case swap immediately. This is not supported by
+    /// every driver.
+    AdaptiveVSync,
+}
+
+impl SwapInterval {
+    fn to_sdl(&self) -> c_int {
+        match *self {
+            Immediate       => 0,
+            VSync           => 1,
+            AdaptiveVSync   => -1,
+        }
+    }
+
+    fn from_sdl(interval: c_int) -> SwapInterval {
+        match interval {
+            0               => Immediate,
+            interval if interval < 0 => AdaptiveVSync,
+            _               => VSync,
+        }
+    }
+}
+
+/// An OpenGL context, which is deleted when dropped. Created with
+/// `Window::gl_create_context` or `GLContext::create_shared`.
+///
+/// Each thread has at most one current context, which SDL keeps track of.
+/// The current context can be queried with `is_current`, or with
+/// `VideoSubsystem::gl_current_context`.
+pub struct GLContext<'a> {
+    raw: SDL_GLContext,
+    window: &'a Window,
+}
+
+impl<'a> GLContext<'a> {
+    /// Returns the underlying SDL context, for use with the `ffi` functions.
+    pub fn raw(&self) -> SDL_GLContext {
+        self.raw
+    }
+
+    /// Returns the window the context renders to.
+    pub fn window(&self) -> &'a Window {
+        self.window
+    }
+
+    /// Makes this the current context of the thread, rendering to its window.
+    pub fn make_current(&self) -> SdlResult<()> {
+        if unsafe { ffi::video::SDL_GL_MakeCurrent(self.window.raw, self.raw) } == 0 {
+            Ok(())
+        } else {
+            Err(get_error())
+        }
+    }
+
+    /// Makes this the current context of the thread, rendering to another
+    /// window. The window must have been created with the same OpenGL
+    /// attributes as the context's own window. If the window is dropped
+    /// while the context is current, no context is left current.
+    pub fn make_current_with(&self, window: &Window) -> SdlResult<()> {
+        if unsafe { ffi::video::SDL_GL_MakeCurrent(window.raw, self.raw) } == 0 {
+            Ok(())
+        } else {
+            Err(get_error())
+        }
+    }
+
+    /// Makes this the current context of the thread, rendering to the
+    /// window, while calling `f`. The context and window that were current
+    /// beforehand are restored afterwards, unless the window has since been
+    /// destroyed.
+    ///
+    /// # Example
+    ///
+    /// ~~~
+    /// for window in windows.iter() {
+    ///     context.with_current(window, || {
+    ///         // draw with OpenGL
+    ///         window.gl_swap_window();
+    ///     }).unwrap();
+    /// }
+    /// ~~~
+    pub fn with_current<T>(&self, window: &Window, f: || -> T) -> SdlResult<T> {
+        let (previous_window, previous_context) = unsafe {
+            (ffi::video::SDL_GL_GetCurrentWindow(), ffi::video::SDL_GL_GetCurrentContext())
+        };
+        // The id identifies the previous window even if its memory is reused
+        let previous_id = if previous_window.is_null() {
+            0
+        } else {
+            unsafe { ffi::video::SDL_GetWindowID(previous_window) }
+        };
+
+        try!(self.make_current_with(window));
+        let result = f();
+
+        unsafe {
+            if previous_id != 0 && ffi::video::SDL_GetWindowFromID(previous_id) == previous_window {
+                ffi::video::SDL_GL_MakeCurrent(previous_window, previous_context);
+            } else {
+                ffi::video::SDL_GL_MakeCurrent(ptr::null(), ptr::null());
+            }
+        }
+        Ok(result)
+    }
+
+    /// Creates a context for the window that shares objects, such as
+    /// textures and buffers, with this one. This context is made current in
+    /// order to share it, then the new context is made current in its place.
+    ///
+    /// # Example
+    ///
+    /// ~~~
+    /// let main_context = main_window.gl_create_context().unwrap();
+    /// let preview_context = main_context.create_shared(&preview_window).unwrap();
+    /// // Textures uploaded with either context can be used with both
+    /// ~~~
+    pub fn create_shared<'b>(&self, window: &'b Window) -> SdlResult<GLContext<'b>> {
+        try!(self.make_current());
+
+        let attr = ffi::video::SDL_GL_SHARE_WITH_CURRENT_CONTEXT;
+        let mut previous = 0;
+        unsafe {
+            ffi::video::SDL_GL_GetAttribute(attr, &mut previous);
+            if ffi::video::SDL_GL_SetAttribute(attr, 1) != 0 {
+                return Err(get_error());
+            }
+        }
+        let context = window.gl_create_context();
+        unsafe { ffi::video::SDL_GL_SetAttribute(attr, previous) };
+        context
+    }
+
+    /// Returns `true` if this is the current context of the thread.
+    pub fn is_current(&self) -> bool {
+        unsafe { ffi::video::SDL_GL_GetCurrentContext() == self.raw }
+    }
+
+    /// Presents the frame drawn to the context's window.
+    pub fn swap_window(&self) {
+        self.window.gl_swap_window()
+    }
+}
+
+#[unsafe_destructor]
+impl<'a> Drop for GLContext<'a> {
+    fn drop(&mut self) {
+        unsafe { ffi::video::SDL_GL_DeleteContext(self.raw) }
+    }
+}
+
+/// OpenGL attributes to request before creating an OpenGL window and
+/// context. Attributes that are not set keep their current value.
+///
+/// # Example
+///
+/// ~~~
+/// let video = sdl2.video();
+/// let requested = sdl2::video::GLAttributes::new()
+///     .context_version(3, 2)
+///     .context_profile(sdl2::ffi::video::SDL_GL_CONTEXT_PROFILE_CORE)
+///     .depth_size(24)
+///     .multisample(1, 4);
+/// requested.apply(&video).unwrap();
+///
+/// let window = sdl2::video::WindowBuilder::new("GL", 800, 600)
+///     .opengl()
+///     .build(&sdl2).unwrap();
+/// let context = window.gl_create_context().unwrap();
+///
+/// for attr in requested.granted(&video).unwrap().iter().filter(|a| !a.is_match()) {
+///     println!("{}: requested {}, got {}", attr.name(), attr.requested, attr.granted);
+/// }
+/// ~~~
+pub struct GLAttributes {
+    attrs: Vec<(SDL_GLattr, c_int)>,
+}
+
+impl GLAttributes {
+    pub fn new() -> GLAttributes {
+        GLAttributes { attrs: Vec::new() }
+    }
+
+    fn with_attr(mut self, attr: SDL_GLattr, value: c_int) -> GLAttributes {
+        self.attrs.retain(|&(a, _)| a as c_int != attr as c_int);
+        self.attrs.push((attr, value));
+        self
+    }
+
+    /// Sets the minimum number of bits for each colour channel.
+    pub fn color_size(self, red: int, green: int, blue: int, alpha: int) -> GLAttributes {
+        self.with_attr(ffi::video::SDL_GL_RED_SIZE, red as c_int)
+            .with_attr(ffi::video::SDL_GL_GREEN_SIZE, green as c_int)
+            .with_attr(ffi::video::SDL_GL_BLUE_SIZE, blue as c_int)
+            .with_attr(ffi::video::SDL_GL_ALPHA_SIZE, alpha as c_int)
+    }
+
+    /// Sets the minimum number of bits in the depth buffer.
+    pub fn depth_size(self, size: int) -> GLAttributes {
+        self.with_attr(ffi::video::SDL_GL_DEPTH_SIZE, size as c_int)
+    }
+
+    /// Sets the minimum number of bits in the stencil buffer.
+    pub fn stencil_size(self, size: int) -> GLAttributes {
+        self.with_attr(ffi::video::SDL_GL_STENCIL_SIZE, size as c_int)
+    }
+
+    /// Sets the minimum number of bits for each channel of the accumulation
+    /// buffer.
+    pub fn accum_size(self, red: int, green: int, blue: int, alpha: int) -> GLAttributes {
+        self.with_attr(ffi::video::SDL_GL_ACCUM_RED_SIZE, red as c_int)
+            .with_attr(ffi::video::SDL_GL_ACCUM_GREEN_SIZE, green as c_int)
+            .with_attr(ffi::video::SDL_GL_ACCUM_BLUE_SIZE, blue as c_int)
+            .with_attr(ffi::video::SDL_GL_ACCUM_ALPHA_SIZE, alpha as c_int)
+    }
+
+    pub fn double_buffer(self, enabled: bool) -> GLAttributes {
+        self.with_attr(ffi::video::SDL_GL_DOUBLEBUFFER, enabled as c_int)
+    }
+
+    /// Sets the number of multisample buffers, which is `0` or `1`, and the
+    /// number of samples per pixel used for anti-aliasing.
+    pub fn multisample(self, buffers: int, samples: int) -> GLAttributes {
+        self.with_attr(ffi::video::SDL_GL_MULTISAMPLEBUFFERS, buffers as c_int)
+            .with_attr(ffi::video::SDL_GL_MULTISAMPLESAMPLES, samples as c_int)
+    }
+
+    pub fn context_version(self, major: int, minor: int) -> GLAttributes {
+        self.with_attr(ffi::video::SDL_GL_CONTEXT_MAJOR_VERSION, major as c_int)
+            .with_attr(ffi::video::SDL_GL_CONTEXT_MINOR_VERSION, minor as c_int)
+    }
+
+    pub fn context_profile(self, profile: SDL_GLprofile) -> GLAttributes {
+        self.with_attr(ffi::video::SDL_GL_CONTEXT_PROFILE_MASK, profile as c_int)
+    }
+
+    pub fn context_flags(self, flags: SDL_GLcontextFlag) -> GLAttributes {
+        self.with_attr(ffi::video::SDL_GL_CONTEXT_FLAGS, flags.bits() as c_int)
+    }
+
+    /// Makes contexts created afterwards share objects, such as textures,
+    /// with the context that is current when they are created.
+    pub fn share_with_current_context(self, share: bool) -> GLAttributes {
+        self.with_attr(ffi::video::SDL_GL_SHARE_WITH_CURRENT_CONTEXT, share as c_int)
+    }
+
+    /// Sets the attributes, which apply to the OpenGL windows and contexts
+    /// created afterwards.
+    pub fn apply(&self, video: &VideoSubsystem) -> SdlResult<()> {
+        for &(attr, value) in self.attrs.iter() {
+            try!(video.gl_set_attribute(attr, value as int));
+        }
+        Ok(())
+    }
+
+    /// Reads back the value granted for each requested attribute, which
+    /// requires the OpenGL context created with them to be current.
+    ///
+    /// SDL only reports the requested context version, profile and flags, so
+    /// these are queried from the context itself. The reset isolation flag
+    /// cannot be queried and is never reported as granted. Whether the
+    /// context shares objects cannot be queried either, so the attribute set
+    /// by `share_with_current_context` is left out.
+    pub fn granted(&self, video: &VideoSubsystem) -> SdlResult<Vec<GLAttributeValue>> {
+        let mut values = Vec::with_capacity(self.attrs.len());
+        let mut context = None;
+        for &(attr, value) in self.attrs.iter() {
+            let granted = match attr {
+                ffi::video::SDL_GL_SHARE_WITH_CURRENT_CONTEXT => continue,
+                ffi::video::SDL_GL_CONTEXT_MAJOR_VERSION | ffi::video::SDL_GL_CONTEXT_MINOR_VERSION |
+                ffi::video::SDL_GL_CONTEXT_PROFILE_MASK | ffi::video::SDL_GL_CONTEXT_FLAGS => {
+                    if context.is_none() {
+                        context = Some(try!(GLContextInfo::query(video)));
+                    }
+                    context.get_ref().attribute(attr)
+                },
+                _ => try!(video.gl_get_attribute(attr)),
+            };
+            values.push(GLAttributeValue {
+                attr: attr,
+                requested: value as int,
+                granted: granted,
+            });
+        }
+        Ok(values)
+    }
+}
+
+// The OpenGL constants needed to query the current context
+static GL_VERSION: c_uint = 0x1F02;
+static GL_CONTEXT_FLAGS: c_uint = 0x821E;
+static GL_CONTEXT_PROFILE_MASK: c_uint = 0x9126;
+static GL_CONTEXT_FLAG_FORWARD_COMPATIBLE_BIT: c_int = 0x1;
+static GL_CONTEXT_FLAG_DEBUG_BIT: c_int = 0x2;
+static GL_CONTEXT_FLAG_ROBUST_ACCESS_BIT: c_int = 0x4;
+
+/// The properties of the current OpenGL context that `SDL_GL_GetAttribute`
+/// only reports as they were requested.
+struct GLContextInfo {
+    major: int,
+    minor: int,
+    profile: int,
+    flags: SDL_GLcontextFlag,
+}
+
+impl GLContextInfo {
+    fn query(video: &VideoSubsystem) -> SdlResult<GLContextInfo> {
+        let get_string: extern "system" fn(c_uint) -> *u8 =
+            match video.gl_get_proc_address("glGetString") {
+                Some(address) => unsafe { transmute(address) },
+                None => return Err(~"the OpenGL function glGetString is not available"),
+            };
+        let get_integer: extern "system" fn(c_uint, *mut c_int) =
+            match video.gl_get_proc_address("glGetIntegerv") {
+                Some(address) => unsafe { transmute(address) },
+                None => return Err(~"the OpenGL function glGetIntegerv is not available"),
+            };
+
+        let version = get_string(GL_VERSION);
+        if version.is_null() {
+            return Err(~"the OpenGL version could not be queried, is a context current?");
+        }
+        let version = unsafe { str::raw::from_c_str(version as *c_char) };
+        let (major, minor) = match parse_gl_version(version) {
+            Some(version) => version,
+            None => return Err(format!("unrecognised OpenGL version `{}`", version)),
+        };
+        let is_es = version.starts_with("OpenGL ES");
+
+        // Older contexts have neither flags nor profiles, and querying them
+        // would leave an error for the application to find
+        let mut gl_flags = 0;
+        if (!is_es && major >= 3) || (is_es && (major, minor) >= (3, 2)) {
+            get_integer(GL_CONTEXT_FLAGS, &mut gl_flags);
+        }
+        let mut gl_profile = 0;
+        if !is_es && (major, minor) >= (3, 2) {
+            get_integer(GL_CONTEXT_PROFILE_MASK, &mut gl_profile);
+        }
+
+        // The OpenGL flags do not use the same bits as SDL's
+        let mut flags = SDL_GLcontextFlag::empty();
+        if gl_flags & GL_CONTEXT_FLAG_DEBUG_BIT != 0 {
+            flags = flags | ffi::video::SDL_GL_CONTEXT_DEBUG_FLAG;
+        }
+        if gl_flags & GL_CONTEXT_FLAG_FORWARD_COMPATIBLE_BIT != 0 {
+            flags = flags | ffi::video::SDL_GL_CONTEXT_FORWARD_COMPATIBLE_FLAG;
+        }
+        if gl_flags & GL_CONTEXT_FLAG_ROBUST_ACCESS_BIT != 0 {
+            flags = flags | ffi::video::SDL_GL_CONTEXT_ROBUST_ACCESS_FLAG;
+        }
+
+        Ok(GLContextInfo {
+            major: major,
+            minor: minor,
+            // The profile bits are the same as SDL's
+            profile: if is_es { ffi::video::SDL_GL_CONTEXT_PROFILE_ES as int } else { gl_profile as int },
+            flags: flags,
+        })
+    }
+
+    fn attribute(&self, attr: SDL_GLattr) -> int {
+        match attr {
+            ffi::video::SDL_GL_CONTEXT_MAJOR_VERSION    => self.major,
+            ffi::video::SDL_GL_CONTEXT_MINOR_VERSION    => self.minor,
+            ffi::video::SDL_GL_CONTEXT_PROFILE_MASK     => self.profile,
+            _                                           => self.flags.bits() as int,
+        }
+    }
+}
+
+/// Reads the major and minor version from an OpenGL version string, such as
+/// `"4.5.0 NVIDIA 450.80"` or `"OpenGL ES 3.2 Mesa 20.0"`.
+fn parse_gl_version(version: &str) -> Option<(int, int)> {
+    let start = match version.find(|c: char| c.is_digit()) {
+        Some(start) => start,
+        None => return None,
+    };
+    let mut numbers = version.slice_from(start).split(|c: char| !c.is_digit());
+    match (numbers.next().and_then(from_str::<int>), numbers.next().and_then(from_str::<int>)) {
+        (Some(major), Some(minor)) => Some((major, minor)),
+        _ => None,
+    }
+}
+
+/// An OpenGL attribute, with the value that was requested and the value
+/// that the context was created with.
+pub struct GLAttributeValue {
+    pub attr: SDL_GLattr,
+    pub requested: int,
+    pub granted: int,
+}
+
+impl GLAttributeValue {
+    /// Returns `false` if the granted value differs from the requested one.
+    /// Sizes may be larger than requested, so only need to be at least the
+    /// requested size to match.
+    pub fn is_match(&self) -> bool {
+        match self.attr {
+            ffi::video::SDL_GL_RED_SIZE | ffi::video::SDL_GL_GREEN_SIZE |
+            ffi::video::SDL_GL_BLUE_SIZE | ffi::video::SDL_GL_ALPHA_SIZE |
+            ffi::video::SDL_GL_DEPTH_SIZE | ffi::video::SDL_GL_STENCIL_SIZE |
+            ffi::video::SDL_GL_ACCUM_RED_SIZE | ffi::video::SDL_GL_ACCUM_GREEN_SIZE |
+            ffi::video::SDL_GL_ACCUM_BLUE_SIZE | ffi::video::SDL_GL_ACCUM_ALPHA_SIZE |
+            ffi::video::SDL_GL_MULTISAMPLESAMPLES => self.granted >= self.requested,
+            _ => self.granted == self.requested,
+        }
+    }
+
+    /// Returns the name of the attribute, for logging.
+    pub fn name(&self) -> &'static str {
+        match self.attr {
+            ffi::video::SDL_GL_RED_SIZE                     => "red size",
+            ffi::video::SDL_GL_GREEN_SIZE                   => "green size",
+            ffi::video::SDL_GL_BLUE_SIZE                    => "blue size",
+            ffi::video::SDL_GL_ALPHA_SIZE                   => "alpha size",
+            ffi::video::SDL_GL_BUFFER_SIZE                  => "buffer size",
+            ffi::video::SDL_GL_DOUBLEBUFFER                 => "double buffer",
+            ffi::video::SDL_GL_DEPTH_SIZE                   => "depth size",
+            ffi::video::SDL_GL_STENCIL_SIZE                 => "stencil size",
+            ffi::video::SDL_GL_ACCUM_RED_SIZE               => "accumulation red size",
+            ffi::video::SDL_GL_ACCUM_GREEN_SIZE             => "accumulation green size",
+            ffi::video::SDL_GL_ACCUM_BLUE_SIZE              => "accumulation blue size",
+            ffi::video::SDL_GL_ACCUM_ALPHA_SIZE             => "accumulation alpha size",
+            ffi::video::SDL_GL_STEREO                       => "stereo",
+            ffi::video::SDL_GL_MULTISAMPLEBUFFERS           => "multisample buffers",
+            ffi::video::SDL_GL_MULTISAMPLESAMPLES           => "multisample samples",
+            ffi::video::SDL_GL_ACCELERATED_VISUAL           => "accelerated visual",
+            ffi::video::SDL_GL_RETAINED_BACKING             => "retained backing",
+            ffi::video::SDL_GL_CONTEXT_MAJOR_VERSION        => "context major version",
+            ffi::video::SDL_GL_CONTEXT_MINOR_VERSION        => "context minor version",
+            ffi::video::SDL_GL_CONTEXT_EGL                  => "context EGL",
+            ffi::video::SDL_GL_CONTEXT_FLAGS                => "context flags",
+            ffi::video::SDL_GL_CONTEXT_PROFILE_MASK         => "context profile",
+            ffi::video::SDL_GL_SHARE_WITH_CURRENT_CONTEXT   => "share with current context",
+        }
+    }
+}
+
+/// OpenGL
+impl VideoSubsystem {
+    /// Sets an attribute for the OpenGL windows and contexts created
+    /// afterwards.
+    pub fn gl_set_attribute(&self, attr: SDL_GLattr, value: int) -> SdlResult<()> {
+        if unsafe { ffi::video::SDL_GL_SetAttribute(attr, value as c_int) } == 0 {
+            Ok(())
+        } else {
+            Err(get_error())
+        }
+    }
+
+    /// Loads the OpenGL library from the path, or the default library if
+    /// `None` is given. This must be called before the first OpenGL window
+    /// is created; otherwise the library is loaded when it is needed.
+    pub fn gl_load_library(&self, path: Option<&Path>) -> SdlResult<()> {
+        let result = match path {
+            Some(path) => path.with_c_str(|path| unsafe { ffi::video::SDL_GL_LoadLibrary(path) }),
+            None => unsafe { ffi::video::SDL_GL_LoadLibrary(ptr::null()) },
+        };
+        if result == 0 { Ok(()) } else { Err(get_error()) }
+    }
+
+    /// Unloads the library loaded with `gl_load_library`.
+    pub fn gl_unload_library(&self) {
+        unsafe { ffi::video::SDL_GL_UnloadLibrary() }
+    }
+
+    /// Returns the address of an OpenGL function, or `None` if it could not
+    /// be found. The address may be returned even if the current context does
+    /// not support the function, so extensions should be checked first with
+    /// `gl_extension_supported`.
+    ///
+    /// # Example
+    ///
+    /// ~~~
+    /// let video = sdl2.video();
+    /// gl::load_with(|name| video.gl_get_proc_address(name).unwrap_or(std::ptr::null()));
+    /// ~~~
+    pub fn gl_get_proc_address(&self, name: &str) -> Option<*c_void> {
+        let address = name.with_c_str(|name| unsafe { ffi::video::SDL_GL_GetProcAddress(name) });
+        if address.is_null() { None } else { Some(address) }
+    }
+
+    /// Returns `true` if the current OpenGL context supports the extension.
+    pub fn gl_extension_supported(&self, extension: &str) -> bool {
+        extension.with_c_str(|extension| unsafe {
+            ffi::video::SDL_GL_ExtensionSupported(extension).to_bool()
+        })
+    }
+
+    /// Returns the value of an attribute for the current OpenGL context.
+    pub fn gl_get_attribute(&self, attr: SDL_GLattr) -> SdlResult<int> {
+        let mut value = 0;
+        if unsafe { ffi::video::SDL_GL_GetAttribute(attr, &mut value) } == 0 {
+            Ok(value as int)
+        } else {
+            Err(get_error())
+        }
+    }
+
+    /// Returns the id of the window targeted by the current OpenGL context of
+    /// this thread, or `None` if no context is current.
+    pub fn gl_current_window_id(&self) -> Option<u32> {
+        let window = unsafe { ffi::video::SDL_GL_GetCurrentWindow() };
+        if window.is_null() {
+            None
+        } else {
+            Some(unsafe { ffi::video::SDL_GetWindowID(window) })
+        }
+    }
+
+    /// Returns the current OpenGL context of this thread, or `None` if no
+    /// context is current. Compare the result with `GLContext::raw`.
+    pub fn gl_current_context(&self) -> Option<SDL_GLContext> {
+        let context = unsafe { ffi::video::SDL_GL_GetCurrentContext() };
+        if context.is_null() { None } else { Some(context) }
+    }
+
+    /// Leaves this thread without a current OpenGL context.
+    pub fn gl_release_current_context(&self) -> SdlResult<()> {
+        if unsafe { ffi::video::SDL_GL_MakeCurrent(ptr::null(), ptr::null()) } == 0 {
+            Ok(())
+        } else {
+            Err(get_error())
+        }
+    }
+
+    /// Sets the swap interval of the current OpenGL context.
+    pub fn gl_set_swap_interval(&self, interval: SwapInterval) -> SdlResult<()> {
+        if unsafe { ffi::video::SDL_GL_SetSwapInterval(interval.to_sdl()) } == 0 {
+            Ok(())
+        } else {
+            Err(get_error())
+        }
+    }
+
+    /// Returns the swap interval of the current OpenGL context.
+    pub fn gl_swap_interval(&self) -> SwapInterval {
+        SwapInterval::from_sdl(unsafe { ffi::video::SDL_GL_GetSwapInterval() })
+    }
+}
+
+/// Owns a set of windows, and finds them by the ids that SDL reports in
+/// events and focus queries.
+///
+/// # Example
+///
+/// ~~~
+/// let mut windows = sdl2::video::WindowRegistry::new();
+/// let main = sdl2::video::WindowBuilder::new("Main", 800, 600).build(&sdl2).unwrap();
+/// let tools = sdl2::video::WindowBuilder::new("Tools", 200, 600).build(&sdl2).unwrap();
+/// windows.insert(main);
+/// windows.insert(tools);
+///
+/// for (_, event) in sdl2.poll_event_iter() {
+///     windows.dispatch(&event, |window, event| {
+///         match *event {
+///             sdl2::event::WindowEvent { kind: sdl2::event::Close, .. } => window.hide(),
+///             _ => {},
+///         }
+///     });
+/// }
+/// ~~~
+pub struct WindowRegistry {
+    windows: HashMap<u32, Window>,
+}
+
+impl WindowRegistry {
+    pub fn new() -> WindowRegistry {
+        WindowRegistry { windows: HashMap::new() }
+    }
+
+    /// Takes ownership of the window, returning its id.
+    pub fn insert(&mut self, window: Window) -> u32 {
+        let id = window.id();
+        self.windows.insert(id, window);
+        id
+    }
+
+    /// Gives up ownership of the window with the id, if it is registered.
+    pub fn remove(&mut self, id: u32) -> Option<Window> {
+        self.windows.pop(&id)
+    }
+
+    /// Returns the registered window with the id. Windows that SDL no longer
+    /// recognises are never returned.
+    pub fn get<'a>(&'a self, id: u32) -> Option<&'a Window> {
+        match self.windows.find(&id) {
+            Some(window) if unsafe { ffi::video::SDL_GetWindowFromID(id) } == window.raw => Some(window),
+            _ => None,
+        }
+    }
+
+    pub fn get_mut<'a>(&'a mut self, id: u32) -> Option<&'a mut Window> {
+        match self.windows.find_mut(&id) {
+            Some(window) if unsafe { ffi::video::SDL_GetWindowFromID(id) } == window.raw => Some(window),
+            _ => None,
+        }
+    }
+
+    /// Returns the registered window that an SDL window pointer refers to.
+    pub fn find_raw<'a>(&'a self, raw: *SDL_Window) -> Option<&'a Window> {
+        if raw.is_null() { return None }
+        self.get(unsafe { ffi::video::SDL_GetWindowID(raw) })
+    }
+
+    /// Returns the registered window that the event was reported for.
+    pub fn window_for_event<'a>(&'a self, event: &Event) -> Option<&'a Window> {
+        event.window_id().and_then(|id| self.get(id))
+    }
+
+    /// Calls `f` with the registered window that the event was reported for,
+    /// returning `false` if there is no such window.
+    pub fn dispatch(&mut self, event: &Event, f: |&mut Window, &Event|) -> bool {
+        match event.window_id().and_then(|id| self.get_mut(id)) {
+            Some(window) => { f(window, event); true },
+            None => false,
+        }
+    }
+
+    /// Returns the registered window that has keyboard focus.
+    pub fn keyboard_focus<'a>(&'a self) -> Option<&'a Window> {
+        self.find_raw(unsafe { ffi::keyboard::SDL_GetKeyboardFocus() })
+    }
+
+    /// Returns the registered window that has mouse focus.
+    pub fn mouse_focus<'a>(&'a self) -> Option<&'a Window> {
+        self.find_raw(unsafe { ffi::mouse::SDL_GetMouseFocus() })
+    }
+
+    pub fn len(&self) -> uint {
+        self.windows.len()
+    }
+
+    /// Returns an iterator over the ids and registered windows, in no
+    /// particular order.
+    pub fn iter<'a>(&'a self) -> Entries<'a, u32, Window> {
+        self.windows.iter()
+    }
+}